@@ -1,4 +1,6 @@
-use mystic_light_sdk::{Color, CommonError, DeviceLedState, MysticLightSDK};
+#![cfg_attr(not(windows), allow(dead_code, unused_imports))]
+
+use mystic_light_sdk::{Color, CommonError, DeviceLedState, LedStyle, MysticLightSDK};
 use std::thread;
 use std::time::Duration;
 
@@ -11,6 +13,7 @@ const LIB_PATH: &str = if cfg!(target_arch = "x86_64") {
     "../sdk/MysticLight_SDK.dll"
 };
 
+#[cfg(windows)]
 fn main() -> Result<(), CommonError> {
     fmt()
         .pretty()
@@ -20,13 +23,14 @@ fn main() -> Result<(), CommonError> {
 
     let sdk = MysticLightSDK::new(LIB_PATH)?;
 
-    let devices: Vec<_> = sdk.devices_iter().collect();
+    // devices are in the order they are enumerated by the sdk
+    let devices = sdk.devices();
 
     info!(?devices);
 
-    info!(second_device_name = devices[2].name());
+    info!(third_device_name = devices[2].name());
 
-    let keyboard_leds: Vec<_> = devices[2].leds_iter().collect();
+    let keyboard_leds = devices[2].leds();
 
     info!(?keyboard_leds);
 
@@ -49,7 +53,7 @@ fn main() -> Result<(), CommonError> {
             green: 0,
             blue: 0,
         },
-        style: String::from("NoAnimation"),
+        style: LedStyle::NoAnimation,
         ..state
     };
 
@@ -63,3 +67,8 @@ fn main() -> Result<(), CommonError> {
 
     Ok(())
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("Mystic Light SDK is available only on Windows");
+}
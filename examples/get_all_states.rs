@@ -1,3 +1,5 @@
+#![cfg_attr(not(windows), allow(dead_code, unused_imports))]
+
 use mystic_light_sdk::{CommonError, MysticLightSDK};
 use tracing::{info, Level};
 use tracing_subscriber::{fmt, fmt::format::FmtSpan};
@@ -8,6 +10,7 @@ const LIB_PATH: &str = if cfg!(target_arch = "x86_64") {
     "../sdk/MysticLight_SDK.dll"
 };
 
+#[cfg(windows)]
 fn main() -> Result<(), CommonError> {
     fmt()
         .pretty()
@@ -32,3 +35,8 @@ fn main() -> Result<(), CommonError> {
 
     Ok(())
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("Mystic Light SDK is available only on Windows");
+}
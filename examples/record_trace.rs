@@ -0,0 +1,42 @@
+#![cfg_attr(not(windows), allow(dead_code, unused_imports))]
+
+#[cfg(windows)]
+use mystic_light_sdk::LibraryBackend;
+use mystic_light_sdk::{MysticLightSDK, RecordingBackend};
+use tracing::{info, Level};
+use tracing_subscriber::{fmt, fmt::format::FmtSpan};
+
+const LIB_PATH: &str = if cfg!(target_arch = "x86_64") {
+    "../sdk/MysticLight_SDK_x64.dll"
+} else {
+    "../sdk/MysticLight_SDK.dll"
+};
+
+const TRACE_PATH: &str = "trace.jsonl";
+
+#[cfg(windows)]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    fmt()
+        .pretty()
+        .with_max_level(Level::DEBUG)
+        .with_span_events(FmtSpan::ACTIVE)
+        .init();
+
+    let backend = RecordingBackend::to_file(LibraryBackend::new(LIB_PATH)?, TRACE_PATH)?;
+    let sdk = MysticLightSDK::with_backend(backend)?;
+
+    for device in sdk.devices_iter() {
+        for led in device.leds_iter() {
+            info!(device = device.name(), led = led.name(), state = ?led.get_state());
+        }
+    }
+
+    info!("Trace is written to {TRACE_PATH}");
+
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("Mystic Light SDK is available only on Windows");
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(not(windows), allow(dead_code, unused_imports))]
+
 use mystic_light_sdk::MysticLightSDK;
 
 const LIB_PATH: &str = if cfg!(target_arch = "x86_64") {
@@ -6,14 +8,16 @@ const LIB_PATH: &str = if cfg!(target_arch = "x86_64") {
     "../sdk/MysticLight_SDK.dll"
 };
 
+#[cfg(windows)]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let sdk = MysticLightSDK::new(LIB_PATH)?;
 
-    let devices: Vec<_> = sdk.devices_iter().collect();
+    // devices are in the order they are enumerated by the sdk
+    let devices = sdk.devices();
 
     println!("devices json: {}", serde_json::to_string_pretty(&devices)?);
 
-    let keyboard_leds: Vec<_> = devices[2].leds_iter().collect();
+    let keyboard_leds = devices[2].leds();
 
     println!(
         "keyboard_leds json: {}",
@@ -29,3 +33,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("Mystic Light SDK is available only on Windows");
+}
@@ -0,0 +1,43 @@
+use mystic_light_sdk::{Color, DeviceLedStateInput, LedStyle, MysticLightSDK, SimulatedBackend};
+use tracing::{info, Level};
+use tracing_subscriber::{fmt, fmt::format::FmtSpan};
+
+const FIXTURE_PATH: &str = "examples/fixtures/simulated.json";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    fmt()
+        .pretty()
+        .with_max_level(Level::DEBUG)
+        .with_span_events(FmtSpan::ACTIVE)
+        .init();
+
+    let backend = SimulatedBackend::from_fixture_file(FIXTURE_PATH)?;
+    let sdk = MysticLightSDK::with_backend(backend)?;
+
+    for device in sdk.devices_iter() {
+        for led in device.leds_iter() {
+            info!(device = device.name(), led = led.name(), state = ?led.get_state()?);
+        }
+    }
+
+    let led = sdk
+        .devices_iter()
+        .flat_map(|device| device.leds_iter())
+        .find(|led| led.name() == "JRAINBOW1")
+        .expect("led is defined in the fixture");
+
+    led.merge_with_state(&DeviceLedStateInput {
+        style: Some(LedStyle::Breathing),
+        color: Some(Color {
+            red: 0,
+            green: 128,
+            blue: 255,
+        }),
+        bright: None,
+        speed: None,
+    })?;
+
+    info!(led = led.name(), state = ?led.get_state()?);
+
+    Ok(())
+}
@@ -38,25 +38,39 @@
 //! You may use build script included in the library itself to copy directory with sdk to the output directory. To do so provide environment variable `MYSTIC_LIGHT_SDK_PATH`
 //! with **absolute** path to directory with the sdk's dll e.g. `MYSTIC_LIGHT_SDK_PATH=/workspaces/project/sdk`.
 //!
-//! # Panics
-//!
-//! - in case of any problems with conversion from and into WinApi types
-//!
 //! # How does it work
 //!
 //! ## Parallelism
 //!
-//! Underlying C++ SDK doesn't support parallel access and trying to use sdk that way will lead to wrong data. To prevent such problems this wrapper wraps underlying library in Arc and Mutex.
-//! Arc is used to share the same library instance across wrapper structs. Mutex is used to prevent parallel access to the underlying library.
+//! Underlying C++ SDK doesn't support parallel access and trying to use sdk that way will lead to wrong data. To prevent such problems this wrapper wraps underlying backend in Arc and Mutex.
+//! Arc is used to share the same backend instance across wrapper structs. Mutex is used to prevent parallel access to the underlying backend.
 //!
 //! That all means you can safely use rust wrapper both in single-threaded and multi-threaded environments, but actual sdk calls will be executed in sequence anyway.
 //!
+//! If the sdk should be called from the single thread only (e.g. in async runtimes that move tasks between threads) use [SdkHandle] as a backend.
+//! It owns the backend on the dedicated worker thread and sends all of the calls there over the channel.
+//!
+//! ## Backends
+//!
+//! All of the calls to the sdk are made through the [LightingBackend] trait. [MysticLightSDK::new] uses [LibraryBackend] that loads the actual dll (available only on Windows),
+//! but any other implementation can be passed to the [MysticLightSDK::with_backend] e.g. to run your code without the MSI hardware.
+//!
+//! Different versions of the MSI software ship dlls with different sets of functions. Optional functions that are available
+//! are reported by [MysticLightSDK::capabilities]. Without them the wrapper falls back to the other calls where possible
+//! e.g. sub-led colors are written one by one if `MLAPI_SetLedColors` is missing.
+//!
 //! # Usage
 //!
 //! ## tracing
 //!
 //! Tracing is implemented with library [`tracing`](https://docs.rs/tracing/0.1.36/tracing/index.html) - to see tracing logs follow the [instructions of tracing crate](https://docs.rs/tracing/0.1.36/tracing/index.html#in-executables).
 //!
+//! ## async
+//!
+//! Methods with the `_async` suffix (e.g. [DeviceLed::get_state_async], [MysticLightSDK::with_backend_async]) return futures
+//! that don't block the caller. Sdk calls are executed on the thread of the backend, so these futures may be awaited by any async runtime
+//! and the calls to the different backends don't wait for each other.
+//!
 //! # Features
 //!
 //! ## serde
@@ -70,6 +84,8 @@
 //! When this feature is enabled you can use [MysticLightGraphqlQuery] as async_graphql::Query and [MysticLightGraphqlMutation] as async_graphql::Mutation
 //!
 //! ```
+//! # #[cfg(feature = "async-graphql")]
+//! # mod graphql {
 //! use async_graphql::{EmptySubscription, Schema};
 //! use mystic_light_sdk::{build_graphql_schema, MysticLightSDK, MysticLightGraphqlMutation, MysticLightGraphqlQuery};
 //!
@@ -80,7 +96,77 @@
 //!
 //!     Schema::build(query, mutation, EmptySubscription).finish()
 //! }
+//! # }
+//! ```
+//!
+//! ## simulated
+//!
+//! Enables [SimulatedBackend] - in-memory backend that simulates MSI hardware described by the json fixture.
+//! Might be used to develop and test code on any os without the actual hardware
+//!
+//! ```
+//! # #[cfg(feature = "simulated")]
+//! # {
+//! use mystic_light_sdk::{MysticLightSDK, SimulatedBackend};
+//!
+//! let backend = SimulatedBackend::from_fixture_file("examples/fixtures/simulated.json").unwrap();
+//! let sdk = MysticLightSDK::with_backend(backend).unwrap();
+//! # }
+//! ```
+//!
+//! ## recording
+//!
+//! Enables [RecordingBackend] that writes every call made through the wrapped backend into jsonl trace
+//! and [ReplayBackend] that serves recorded trace back. Allows to record the issue on the real hardware and reproduce it later on any os
+//!
+//! ```
+//! # #[cfg(feature = "recording")]
+//! # {
+//! use mystic_light_sdk::{MysticLightSDK, ReplayBackend};
+//!
+//! let backend = ReplayBackend::from_trace_file("examples/fixtures/trace.jsonl").unwrap();
+//! let sdk = MysticLightSDK::with_backend(backend).unwrap();
+//! # }
+//! ```
+//!
+//! ## remote
+//!
+//! Enables [RemoteServer] that shares the local sdk over tcp and [RemoteBackend] that connects to it.
+//! Allows to control the leds of the another machine with the same api as the local ones
+//!
+//! ```ignore
+//! use mystic_light_sdk::{MysticLightSDK, RemoteBackend};
+//!
+//! let backend = RemoteBackend::connect("192.168.0.2:6743")?;
+//! let sdk = MysticLightSDK::with_backend(backend)?;
+//! ```
+//!
+//! ## openrgb
+//!
+//! Enables [OpenRgbServer] that speaks the [OpenRGB SDK](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation) network protocol.
+//! Devices are exposed as OpenRGB controllers: leds become zones, supported styles become modes with speed and brightness ranges from the led limits
+//!
+//! ```ignore
+//! use mystic_light_sdk::{MysticLightSDK, OpenRgbServer, OPENRGB_DEFAULT_PORT};
+//!
+//! let sdk = MysticLightSDK::new("sdk/MysticLight_SDK_x64.dll")?;
+//!
+//! OpenRgbServer::bind(sdk, ("0.0.0.0", OPENRGB_DEFAULT_PORT))?.run()?;
+//! ```
+//!
+//! ## cache
+//!
+//! Enables [InventoryCache] that persists devices and their leds on disk. [MysticLightSDK::with_backend_cached] creates devices
+//! from the cache when the connected hardware has the same signature. Revalidation of the cache is opt-in
+//!
+//! ```ignore
+//! use mystic_light_sdk::{InventoryCache, LibraryBackend, MysticLightSDK};
+//!
+//! let cache = InventoryCache::new("inventory.json");
+//! let backend = LibraryBackend::new("sdk/MysticLight_SDK_x64.dll")?;
+//! let sdk = MysticLightSDK::with_backend_cached(backend, cache.clone())?;
 //!
+//! let revalidation = sdk.spawn_cache_revalidation(cache)?;
 //! ```
 //!
 //! # Troubleshooting
@@ -94,6 +180,7 @@
 //! Some of the device's styles do not support colors. In this case this kind of error will be generated.
 //!
 pub mod sdk;
+#[cfg(windows)]
 pub mod winapi;
 
 pub use sdk::*;
@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+/// Optional function of the sdk api
+///
+/// Different versions of the MSI software ship dlls with different export sets.
+/// Functions that are not listed here are required and the dll without them cannot be used at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "async-graphql", derive(async_graphql::Enum))]
+pub enum Capability {
+    /// `MLAPI_GetErrorMessage`. Without it errors have no description
+    GetErrorMessage,
+    /// `MLAPI_GetDeviceName`
+    GetDeviceName,
+    /// `MLAPI_GetDeviceNameEx`. Without it friendly name of the device is resolved with `MLAPI_GetDeviceName` or falls back to the device type name
+    GetDeviceNameEx,
+    /// `MLAPI_GetLedName`. Without it devices have no sub-leds
+    GetLedName,
+    /// `MLAPI_SetLedColors`. Without it sub-led colors are written one by one or, if sub-leds cannot be written at all, the single color is written to the whole led area
+    SetLedColors,
+    /// `MLAPI_SetLedColorEx`. Without it async sub-led writes fall back to `MLAPI_SetLedColorSync`
+    SetLedColorEx,
+    /// `MLAPI_SetLedColorSync`
+    SetLedColorSync,
+}
+
+impl Capability {
+    /// all of the known capabilities
+    pub const ALL: [Capability; 7] = [
+        Capability::GetErrorMessage,
+        Capability::GetDeviceName,
+        Capability::GetDeviceNameEx,
+        Capability::GetLedName,
+        Capability::SetLedColors,
+        Capability::SetLedColorEx,
+        Capability::SetLedColorSync,
+    ];
+
+    /// returns name of the function exported by the sdk dll
+    pub fn export_name(&self) -> &'static str {
+        match self {
+            Capability::GetErrorMessage => "MLAPI_GetErrorMessage",
+            Capability::GetDeviceName => "MLAPI_GetDeviceName",
+            Capability::GetDeviceNameEx => "MLAPI_GetDeviceNameEx",
+            Capability::GetLedName => "MLAPI_GetLedName",
+            Capability::SetLedColors => "MLAPI_SetLedColors",
+            Capability::SetLedColorEx => "MLAPI_SetLedColorEx",
+            Capability::SetLedColorSync => "MLAPI_SetLedColorSync",
+        }
+    }
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.export_name())
+    }
+}
+
+/// Set of the optional sdk functions available in the backend
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities(BTreeSet<Capability>);
+
+impl Capabilities {
+    /// returns set with all of the known capabilities
+    pub fn all() -> Self {
+        Capability::ALL.into_iter().collect()
+    }
+
+    /// returns true if the capability is available
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.0.contains(&capability)
+    }
+
+    /// returns iterator over available capabilities
+    pub fn iter(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// returns iterator over capabilities that are not available
+    pub fn missing(&self) -> impl Iterator<Item = Capability> + '_ {
+        Capability::ALL
+            .into_iter()
+            .filter(|capability| !self.supports(*capability))
+    }
+}
+
+impl FromIterator<Capability> for Capabilities {
+    fn from_iter<T: IntoIterator<Item = Capability>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
@@ -0,0 +1,600 @@
+use std::fmt::Debug;
+use std::ptr::null_mut;
+
+use libloading::Library;
+use winapi::shared::minwindef::DWORD;
+
+use crate::winapi::{Bstr, SafeArray, TryFromSafeArray};
+use crate::{
+    BrightLevel, Color, ColorLevel, CommonError, ConversionError, DeviceIndex, DeviceName,
+    DeviceNames, DeviceTypes, ErrorDescription, LedCounts, LedIndex, LedName, LedNames,
+    LedStyleName, LedStyles, MysticLightSDK, MysticLightSdkResult, Result, SpeedLevel,
+};
+
+use super::{Capabilities, Capability, DeviceInfo, LedInfo, LightingBackend};
+
+type Initialize = unsafe extern "C" fn() -> MysticLightSdkResult;
+type GetErrorMessage = unsafe extern "C" fn(
+    code: MysticLightSdkResult,
+    description: *mut ErrorDescription,
+) -> MysticLightSdkResult;
+type GetDeviceInfo = unsafe extern "C" fn(
+    dev_type: *mut DeviceTypes,
+    led_count: *mut LedCounts,
+) -> MysticLightSdkResult;
+type GetDeviceName = unsafe extern "C" fn(
+    device_name: DeviceName,
+    device_names: *mut DeviceNames,
+) -> MysticLightSdkResult;
+type GetDeviceNameEx = unsafe extern "C" fn(
+    device_name: DeviceName,
+    device_index: DeviceIndex,
+    friendly_name: *mut DeviceName,
+) -> MysticLightSdkResult;
+type GetLedName =
+    unsafe extern "C" fn(device_name: DeviceName, led_names: *mut LedNames) -> MysticLightSdkResult;
+type GetLedInfo = unsafe extern "C" fn(
+    device_name: DeviceName,
+    led_index: LedIndex,
+    led_name: *mut LedName,
+    led_styles: *mut LedStyles,
+) -> MysticLightSdkResult;
+type GetLedStyle = unsafe extern "C" fn(
+    device_name: DeviceName,
+    led_index: LedIndex,
+    style: *mut LedStyleName,
+) -> MysticLightSdkResult;
+type GetLedColor = unsafe extern "C" fn(
+    device_name: DeviceName,
+    led_index: LedIndex,
+    red: *mut ColorLevel,
+    green: *mut ColorLevel,
+    blue: *mut ColorLevel,
+) -> MysticLightSdkResult;
+type GetLedLevel = unsafe extern "C" fn(
+    device_name: DeviceName,
+    led_index: LedIndex,
+    level: *mut u32,
+) -> MysticLightSdkResult;
+type SetLedStyle = unsafe extern "C" fn(
+    device_name: DeviceName,
+    led_index: LedIndex,
+    style: LedStyleName,
+) -> MysticLightSdkResult;
+type SetLedColor = unsafe extern "C" fn(
+    device_name: DeviceName,
+    led_index: LedIndex,
+    r: ColorLevel,
+    g: ColorLevel,
+    b: ColorLevel,
+) -> MysticLightSdkResult;
+type SetLedColors = unsafe extern "C" fn(
+    device_name: DeviceName,
+    area_index: LedIndex,
+    led_names: *mut LedNames,
+    r: *mut ColorLevel,
+    g: *mut ColorLevel,
+    b: *mut ColorLevel,
+) -> MysticLightSdkResult;
+type SetSubLedColor = unsafe extern "C" fn(
+    device_name: DeviceName,
+    area_index: LedIndex,
+    led_name: LedName,
+    r: ColorLevel,
+    g: ColorLevel,
+    b: ColorLevel,
+    force_update: DWORD,
+) -> MysticLightSdkResult;
+type SetLedLevel = unsafe extern "C" fn(
+    device_name: DeviceName,
+    led_index: LedIndex,
+    level: u32,
+) -> MysticLightSdkResult;
+
+/// Looks up the export in the library
+///
+/// # Safety
+///
+/// `T` must be the signature of the exported function
+unsafe fn resolve_export<T: Copy>(library: &Library, name: &str) -> Option<T> {
+    match library.get::<T>(name.as_bytes()) {
+        Ok(symbol) => Some(*symbol),
+        Err(error) => {
+            tracing::warn!(name, %error, "Cannot resolve sdk export");
+
+            None
+        }
+    }
+}
+
+macro_rules! ml_api {
+    (
+        required { $($field:ident: $signature:ty = $export:literal,)* }
+        optional { $($optional_field:ident: $optional_signature:ty = $capability:expr,)* }
+    ) => {
+        /// Functions exported by the sdk dll
+        ///
+        /// Function pointers are valid only while the library they were resolved from is loaded
+        struct MlApi {
+            $($field: $signature,)*
+            $($optional_field: Option<$optional_signature>,)*
+        }
+
+        impl MlApi {
+            /// Resolves all of the sdk exports at once. Returns the list of all missing required exports if any
+            fn resolve(library: &Library) -> Result<Self> {
+                let mut missing = Vec::new();
+
+                $(
+                    let $field = unsafe { resolve_export::<$signature>(library, $export) };
+
+                    if $field.is_none() {
+                        missing.push(String::from($export));
+                    }
+                )*
+
+                match ($($field,)*) {
+                    ($(Some($field),)*) => Ok(Self {
+                        $($field,)*
+                        $($optional_field: unsafe {
+                            resolve_export::<$optional_signature>(library, $capability.export_name())
+                        },)*
+                    }),
+                    _ => Err(CommonError::MissingExports { exports: missing }),
+                }
+            }
+
+            fn capabilities(&self) -> Capabilities {
+                let mut capabilities = Vec::new();
+
+                $(
+                    if self.$optional_field.is_some() {
+                        capabilities.push($capability);
+                    }
+                )*
+
+                capabilities.into_iter().collect()
+            }
+        }
+    };
+}
+
+ml_api! {
+    required {
+        initialize: Initialize = "MLAPI_Initialize",
+        get_device_info: GetDeviceInfo = "MLAPI_GetDeviceInfo",
+        get_led_info: GetLedInfo = "MLAPI_GetLedInfo",
+        get_led_max_bright: GetLedLevel = "MLAPI_GetLedMaxBright",
+        get_led_max_speed: GetLedLevel = "MLAPI_GetLedMaxSpeed",
+        get_led_style: GetLedStyle = "MLAPI_GetLedStyle",
+        get_led_color: GetLedColor = "MLAPI_GetLedColor",
+        get_led_bright: GetLedLevel = "MLAPI_GetLedBright",
+        get_led_speed: GetLedLevel = "MLAPI_GetLedSpeed",
+        set_led_style: SetLedStyle = "MLAPI_SetLedStyle",
+        set_led_color: SetLedColor = "MLAPI_SetLedColor",
+        set_led_bright: SetLedLevel = "MLAPI_SetLedBright",
+        set_led_speed: SetLedLevel = "MLAPI_SetLedSpeed",
+    }
+    optional {
+        get_error_message: GetErrorMessage = Capability::GetErrorMessage,
+        get_device_name: GetDeviceName = Capability::GetDeviceName,
+        get_device_name_ex: GetDeviceNameEx = Capability::GetDeviceNameEx,
+        get_led_name: GetLedName = Capability::GetLedName,
+        set_led_colors: SetLedColors = Capability::SetLedColors,
+        set_led_color_ex: SetSubLedColor = Capability::SetLedColorEx,
+        set_led_color_sync: SetSubLedColor = Capability::SetLedColorSync,
+    }
+}
+
+/// Returns the optional sdk function or error if the dll doesn't export it
+fn optional<T>(function: Option<T>, capability: Capability) -> Result<T> {
+    function.ok_or(CommonError::NotSupportedCapability { capability })
+}
+
+/// Backend that calls the Mystic Light SDK dll
+pub struct LibraryBackend {
+    api: MlApi,
+    // keeps the dll loaded while the functions from `api` are in use
+    _library: Library,
+}
+
+impl Debug for LibraryBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LibraryBackend").finish()
+    }
+}
+
+impl LibraryBackend {
+    /// Load the dll file by the passed path and resolve all of the sdk functions
+    ///
+    /// **You must pass valid dll based on the os architecture**
+    ///
+    /// Returns [`CommonError::MissingExports`] listing every required sdk function the dll doesn't export.
+    /// Missing optional functions are reported by [LightingBackend::capabilities]
+    #[tracing::instrument(level = "debug")]
+    pub fn new(lib_path: &str) -> Result<Self> {
+        let library = unsafe { Library::new(lib_path)? };
+        let api = MlApi::resolve(&library)?;
+
+        Ok(Self {
+            api,
+            _library: library,
+        })
+    }
+
+    /// Converts result code of the sdk call to the Result. Error contains description of the error provided by the sdk
+    fn check(&self, result: MysticLightSdkResult) -> Result<()> {
+        MysticLightSDK::parse_result(result).map_err(|source| {
+            let description = match self.error_message(result) {
+                Ok(description) => Some(description),
+                Err(CommonError::NotSupportedCapability { .. }) => None,
+                Err(error) => {
+                    tracing::warn!(result, %error, "Cannot get error message");
+
+                    None
+                }
+            };
+
+            CommonError::SdkError {
+                source,
+                description,
+            }
+        })
+    }
+
+    fn error_message(&self, code: MysticLightSdkResult) -> Result<String> {
+        let get_error_message = optional(self.api.get_error_message, Capability::GetErrorMessage)?;
+        let mut description: ErrorDescription = null_mut();
+
+        unsafe {
+            MysticLightSDK::parse_result(get_error_message(code, &mut description))?;
+        }
+
+        Ok(Bstr::try_from(description)?.to_string())
+    }
+
+    fn get_led_level(
+        &self,
+        get_led_level: GetLedLevel,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<u32> {
+        let device_name = Bstr::try_from(device_name)?;
+        let mut level = 0u32;
+
+        unsafe {
+            self.check(get_led_level(device_name.as_ptr(), led_index, &mut level))?;
+        }
+
+        Ok(level)
+    }
+
+    fn set_sub_led_color(
+        &self,
+        set_sub_led_color: SetSubLedColor,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        let device_name = Bstr::try_from(device_name)?;
+        let led_name = Bstr::try_from(led_name)?;
+        let &Color { red, green, blue } = color;
+
+        unsafe {
+            self.check(set_sub_led_color(
+                device_name.as_ptr(),
+                area_index,
+                led_name.as_ptr(),
+                red,
+                green,
+                blue,
+                force_update.into(),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    fn set_led_level(
+        &self,
+        set_led_level: SetLedLevel,
+        device_name: &str,
+        led_index: LedIndex,
+        level: u32,
+    ) -> Result<()> {
+        let device_name = Bstr::try_from(device_name)?;
+
+        unsafe {
+            self.check(set_led_level(device_name.as_ptr(), led_index, level))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl LightingBackend for LibraryBackend {
+    fn capabilities(&self) -> Capabilities {
+        self.api.capabilities()
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        unsafe {
+            self.check((self.api.initialize)())?;
+        }
+
+        Ok(())
+    }
+
+    fn get_error_message(&mut self, code: MysticLightSdkResult) -> Result<String> {
+        self.error_message(code)
+    }
+
+    fn get_device_info(&mut self) -> Result<Vec<DeviceInfo>> {
+        let mut dev_type: DeviceTypes = null_mut();
+        let mut led_count: LedCounts = null_mut();
+
+        unsafe {
+            self.check((self.api.get_device_info)(&mut dev_type, &mut led_count))?;
+        }
+
+        let devices_names: Vec<String> = Vec::try_from_safearray(dev_type)?;
+        let leds: Vec<String> = Vec::try_from_safearray(led_count)?;
+
+        devices_names
+            .into_iter()
+            .zip(leds)
+            .map(|(name, led_count)| {
+                let led_count = led_count
+                    .parse()
+                    .map_err(|_| ConversionError::InvalidNumber { value: led_count })?;
+
+                Ok(DeviceInfo { name, led_count })
+            })
+            .collect()
+    }
+
+    fn get_device_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        let get_device_name = optional(self.api.get_device_name, Capability::GetDeviceName)?;
+        let device_name = Bstr::try_from(device_name)?;
+        let mut device_names: DeviceNames = null_mut();
+
+        unsafe {
+            self.check(get_device_name(device_name.as_ptr(), &mut device_names))?;
+        }
+
+        Ok(Vec::try_from_safearray(device_names)?)
+    }
+
+    fn get_device_name_ex(
+        &mut self,
+        device_name: &str,
+        device_index: DeviceIndex,
+    ) -> Result<String> {
+        let get_device_name_ex =
+            optional(self.api.get_device_name_ex, Capability::GetDeviceNameEx)?;
+        let device_name = Bstr::try_from(device_name)?;
+        let mut friendly_name: DeviceName = null_mut();
+
+        unsafe {
+            self.check(get_device_name_ex(
+                device_name.as_ptr(),
+                device_index,
+                &mut friendly_name,
+            ))?;
+        }
+
+        Ok(Bstr::try_from(friendly_name)?.to_string())
+    }
+
+    fn get_led_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        let get_led_name = optional(self.api.get_led_name, Capability::GetLedName)?;
+        let device_name = Bstr::try_from(device_name)?;
+        let mut led_names: LedNames = null_mut();
+
+        unsafe {
+            self.check(get_led_name(device_name.as_ptr(), &mut led_names))?;
+        }
+
+        Ok(Vec::try_from_safearray(led_names)?)
+    }
+
+    fn get_led_info(&mut self, device_name: &str, led_index: LedIndex) -> Result<LedInfo> {
+        let device_name = Bstr::try_from(device_name)?;
+        let mut led_name: LedName = null_mut();
+        let mut led_styles: LedStyles = null_mut();
+
+        unsafe {
+            self.check((self.api.get_led_info)(
+                device_name.as_ptr(),
+                led_index,
+                &mut led_name,
+                &mut led_styles,
+            ))?;
+        }
+
+        Ok(LedInfo {
+            name: Bstr::try_from(led_name)?.to_string(),
+            supported_styles: Vec::try_from_safearray(led_styles)?,
+        })
+    }
+
+    fn get_led_max_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<BrightLevel> {
+        self.get_led_level(self.api.get_led_max_bright, device_name, led_index)
+    }
+
+    fn get_led_max_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        self.get_led_level(self.api.get_led_max_speed, device_name, led_index)
+    }
+
+    fn get_led_style(&mut self, device_name: &str, led_index: LedIndex) -> Result<String> {
+        let device_name = Bstr::try_from(device_name)?;
+        let mut style: LedStyleName = null_mut();
+
+        unsafe {
+            self.check((self.api.get_led_style)(
+                device_name.as_ptr(),
+                led_index,
+                &mut style,
+            ))?;
+        }
+
+        Ok(Bstr::try_from(style)?.to_string())
+    }
+
+    fn get_led_color(&mut self, device_name: &str, led_index: LedIndex) -> Result<Color> {
+        let device_name = Bstr::try_from(device_name)?;
+        let mut red = 0u32;
+        let mut green = 0u32;
+        let mut blue = 0u32;
+
+        unsafe {
+            self.check((self.api.get_led_color)(
+                device_name.as_ptr(),
+                led_index,
+                &mut red,
+                &mut green,
+                &mut blue,
+            ))?;
+        }
+
+        Ok(Color { red, green, blue })
+    }
+
+    fn get_led_bright(&mut self, device_name: &str, led_index: LedIndex) -> Result<BrightLevel> {
+        self.get_led_level(self.api.get_led_bright, device_name, led_index)
+    }
+
+    fn get_led_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        self.get_led_level(self.api.get_led_speed, device_name, led_index)
+    }
+
+    fn set_led_style(&mut self, device_name: &str, led_index: LedIndex, style: &str) -> Result<()> {
+        let device_name = Bstr::try_from(device_name)?;
+        let style = Bstr::try_from(style)?;
+
+        unsafe {
+            self.check((self.api.set_led_style)(
+                device_name.as_ptr(),
+                led_index,
+                style.as_ptr(),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    fn set_led_color(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        color: &Color,
+    ) -> Result<()> {
+        let device_name = Bstr::try_from(device_name)?;
+        let &Color { red, green, blue } = color;
+
+        unsafe {
+            self.check((self.api.set_led_color)(
+                device_name.as_ptr(),
+                led_index,
+                red,
+                green,
+                blue,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    fn set_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()> {
+        let set_led_colors = optional(self.api.set_led_colors, Capability::SetLedColors)?;
+        let device_name = Bstr::try_from(device_name)?;
+        let led_names = SafeArray::try_from_iter(colors.iter().map(|(name, _)| name))?;
+        let mut led_names_ptr = led_names.as_ptr();
+        let mut red: Vec<ColorLevel> = colors.iter().map(|(_, color)| color.red).collect();
+        let mut green: Vec<ColorLevel> = colors.iter().map(|(_, color)| color.green).collect();
+        let mut blue: Vec<ColorLevel> = colors.iter().map(|(_, color)| color.blue).collect();
+
+        unsafe {
+            self.check(set_led_colors(
+                device_name.as_ptr(),
+                area_index,
+                &mut led_names_ptr,
+                red.as_mut_ptr(),
+                green.as_mut_ptr(),
+                blue.as_mut_ptr(),
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    fn set_led_color_ex(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        let set_led_color_ex = optional(self.api.set_led_color_ex, Capability::SetLedColorEx)?;
+
+        self.set_sub_led_color(
+            set_led_color_ex,
+            device_name,
+            area_index,
+            led_name,
+            color,
+            force_update,
+        )
+    }
+
+    fn set_led_color_sync(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        let set_led_color_sync =
+            optional(self.api.set_led_color_sync, Capability::SetLedColorSync)?;
+
+        self.set_sub_led_color(
+            set_led_color_sync,
+            device_name,
+            area_index,
+            led_name,
+            color,
+            force_update,
+        )
+    }
+
+    fn set_led_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    ) -> Result<()> {
+        self.set_led_level(self.api.set_led_bright, device_name, led_index, bright)
+    }
+
+    fn set_led_speed(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    ) -> Result<()> {
+        self.set_led_level(self.api.set_led_speed, device_name, led_index, speed)
+    }
+}
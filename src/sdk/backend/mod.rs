@@ -0,0 +1,195 @@
+//! Backends that execute actual calls on behalf of the sdk wrapper
+//!
+//! [MysticLightSDK](crate::MysticLightSDK), [Device](crate::Device) and [DeviceLed](crate::DeviceLed) never talk to the
+//! underlying dll directly, instead they use some implementation of the [LightingBackend] trait.
+//! This allows to write code that is generic over the backend and to run it without the real MSI hardware.
+
+mod capability;
+#[cfg(windows)]
+mod library;
+#[cfg(feature = "recording")]
+mod recording;
+#[cfg(feature = "simulated")]
+mod simulated;
+mod worker;
+
+use std::sync::{Arc, LockResult, Mutex, MutexGuard};
+
+pub use capability::*;
+#[cfg(windows)]
+pub use library::*;
+#[cfg(feature = "recording")]
+pub use recording::*;
+#[cfg(feature = "simulated")]
+pub use simulated::*;
+pub use worker::*;
+
+use super::color::Color;
+use super::task::{BlockingWorker, Reply};
+use super::types::{BrightLevel, DeviceIndex, LedIndex, MysticLightSdkResult, Result, SpeedLevel};
+
+/// Single device entry as it is returned by the sdk
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    /// device type name e.g. `MSI_MB`
+    pub name: String,
+    /// number of leds the device has
+    pub led_count: u32,
+}
+
+/// Static information about the single led of the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LedInfo {
+    /// led name
+    pub name: String,
+    /// styles that are supported by the led
+    pub supported_styles: Vec<String>,
+}
+
+/// Interface to the Mystic Light SDK api
+///
+/// Every method corresponds to the single `MLAPI_*` function of the underlying sdk.
+/// Methods take `&mut self` as the underlying sdk doesn't support parallel access so the backend is always used exclusively.
+pub trait LightingBackend: Send {
+    /// Returns optional sdk functions supported by the backend. Calls to the unsupported functions fail with [CommonError::NotSupportedCapability](crate::CommonError::NotSupportedCapability)
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::all()
+    }
+
+    /// Initialize the sdk. Called once before any other calls
+    fn initialize(&mut self) -> Result<()>;
+
+    /// Returns human-readable description of the result code
+    fn get_error_message(&mut self, code: MysticLightSdkResult) -> Result<String>;
+
+    /// Returns list of available devices
+    fn get_device_info(&mut self) -> Result<Vec<DeviceInfo>>;
+
+    /// Returns product names of all the devices with the passed type
+    fn get_device_name(&mut self, device_name: &str) -> Result<Vec<String>>;
+
+    /// Returns product name of the device with the passed type and index among devices of the same type
+    fn get_device_name_ex(
+        &mut self,
+        device_name: &str,
+        device_index: DeviceIndex,
+    ) -> Result<String>;
+
+    /// Returns names of the individually addressable sub-leds of the devices with the passed type
+    fn get_led_name(&mut self, device_name: &str) -> Result<Vec<String>>;
+
+    /// Returns name and supported styles of the led
+    fn get_led_info(&mut self, device_name: &str, led_index: LedIndex) -> Result<LedInfo>;
+
+    /// Returns max brightness level of the led
+    fn get_led_max_bright(&mut self, device_name: &str, led_index: LedIndex)
+        -> Result<BrightLevel>;
+
+    /// Returns max speed level of the led
+    fn get_led_max_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel>;
+
+    /// Returns current style of the led
+    fn get_led_style(&mut self, device_name: &str, led_index: LedIndex) -> Result<String>;
+
+    /// Returns current color of the led
+    fn get_led_color(&mut self, device_name: &str, led_index: LedIndex) -> Result<Color>;
+
+    /// Returns current brightness level of the led
+    fn get_led_bright(&mut self, device_name: &str, led_index: LedIndex) -> Result<BrightLevel>;
+
+    /// Returns current speed level of the led
+    fn get_led_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel>;
+
+    /// Set style of the led
+    fn set_led_style(&mut self, device_name: &str, led_index: LedIndex, style: &str) -> Result<()>;
+
+    /// Set color of the led
+    fn set_led_color(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        color: &Color,
+    ) -> Result<()>;
+
+    /// Set colors of the multiple sub-leds inside the led area at once.
+    ///
+    /// `colors` contains pairs of the sub-led name and the color for it
+    fn set_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()>;
+
+    /// Set color of the single sub-led inside the led area. Returns as soon as the request is accepted by the sdk
+    ///
+    /// `force_update` is passed as the trailing flag of the underlying sdk call
+    fn set_led_color_ex(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()>;
+
+    /// Set color of the single sub-led inside the led area. Returns after the color is applied by the hardware
+    ///
+    /// `force_update` is passed as the trailing flag of the underlying sdk call
+    fn set_led_color_sync(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()>;
+
+    /// Set brightness level of the led
+    fn set_led_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    ) -> Result<()>;
+
+    /// Set speed level of the led
+    fn set_led_speed(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    ) -> Result<()>;
+}
+
+/// Backend shared between the sdk wrapper structs together with the worker that executes its async calls
+#[derive(Clone)]
+pub(crate) struct SharedBackend {
+    backend: Arc<Mutex<dyn LightingBackend>>,
+    worker: Arc<BlockingWorker>,
+}
+
+impl SharedBackend {
+    pub(crate) fn new<B: LightingBackend + 'static>(backend: B) -> Self {
+        Self {
+            backend: Arc::new(Mutex::new(backend)),
+            worker: Arc::default(),
+        }
+    }
+
+    /// Acquire exclusive access to the backend
+    pub(crate) fn lock(&self) -> LockResult<MutexGuard<'_, dyn LightingBackend + 'static>> {
+        self.backend.lock()
+    }
+
+    /// Run blocking call on the worker of this backend, see [BlockingWorker::spawn_blocking]
+    pub(crate) fn spawn_blocking<T, F>(&self, f: F) -> Reply<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        self.worker.spawn_blocking(f)
+    }
+}
@@ -0,0 +1,804 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use custom_error::custom_error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    BrightLevel, Color, CommonError, DeviceIndex, LedIndex, MysticLightSDK, MysticLightSdkResult,
+    Result, SpeedLevel,
+};
+
+use super::{Capabilities, DeviceInfo, LedInfo, LightingBackend};
+
+/// Name of the pseudo-call that records capabilities of the backend
+const CAPABILITIES: &str = "capabilities";
+
+custom_error! {
+  /// Errors while loading trace for the [ReplayBackend]
+  #[non_exhaustive]
+  pub TraceError
+      Io{source: std::io::Error} = "Cannot read trace file: {source}",
+      Parse{line: usize, source: serde_json::Error} = "Cannot parse trace line {line}: {source}",
+}
+
+/// Single recorded call to the sdk. Trace is stored as jsonl with one entry per line
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TraceEntry {
+    /// name of the sdk function e.g. `MLAPI_GetLedStyle`
+    pub function: String,
+    /// arguments of the call by their names
+    pub args: Value,
+    /// result code returned by the sdk
+    pub result: MysticLightSdkResult,
+    /// description of the error provided by the sdk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// values returned by the call through the out parameters
+    #[serde(default)]
+    pub output: Value,
+    /// unix timestamp of the call in milliseconds
+    pub timestamp: u64,
+}
+
+impl TraceEntry {
+    fn key(&self) -> (String, String) {
+        (self.function.clone(), self.args.to_string())
+    }
+}
+
+/// Backend that records every call made through the wrapped backend as [TraceEntry] into jsonl
+///
+/// Recorded trace may be served back by the [ReplayBackend] e.g. to reproduce the issue without the actual hardware.
+/// Calls that are failed before reaching the sdk (e.g. because of missing capability) are not recorded
+///
+/// # Examples
+///
+/// ```ignore
+/// use mystic_light_sdk::{LibraryBackend, MysticLightSDK, RecordingBackend};
+///
+/// let backend = RecordingBackend::to_file(LibraryBackend::new("sdk/MysticLight_SDK_x64.dll")?, "trace.jsonl")?;
+/// let sdk = MysticLightSDK::with_backend(backend)?;
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "recording")))]
+pub struct RecordingBackend<B> {
+    backend: B,
+    writer: Box<dyn Write + Send>,
+}
+
+impl<B> std::fmt::Debug for RecordingBackend<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordingBackend").finish()
+    }
+}
+
+impl<B: LightingBackend> RecordingBackend<B> {
+    /// Wrap the backend and write the trace to the passed writer
+    pub fn new(backend: B, writer: impl Write + Send + 'static) -> Self {
+        Self {
+            backend,
+            writer: Box::new(writer),
+        }
+    }
+
+    /// Wrap the backend and write the trace to the file by the passed path. File is truncated if exists
+    pub fn to_file(backend: B, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::new(backend, BufWriter::new(File::create(path)?)))
+    }
+
+    fn write_entry(&mut self, entry: &TraceEntry) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+
+    fn record<T: Serialize>(
+        &mut self,
+        function: &str,
+        args: Value,
+        result: Result<T>,
+    ) -> Result<T> {
+        let (code, description, output) = match &result {
+            Ok(value) => (0, None, json!(value)),
+            Err(CommonError::SdkError {
+                source,
+                description,
+            }) => (source.code(), description.clone(), Value::Null),
+            Err(error) => {
+                tracing::debug!(function, %error, "Call is not recorded as it didn't reach the sdk");
+
+                return result;
+            }
+        };
+
+        let entry = TraceEntry {
+            function: function.to_owned(),
+            args,
+            result: code,
+            description,
+            output,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default(),
+        };
+
+        if let Err(error) = self.write_entry(&entry) {
+            tracing::warn!(function, %error, "Cannot write trace entry");
+        }
+
+        result
+    }
+}
+
+impl<B: LightingBackend> LightingBackend for RecordingBackend<B> {
+    fn capabilities(&self) -> Capabilities {
+        self.backend.capabilities()
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        let result = self.backend.initialize();
+        let result = self.record("MLAPI_Initialize", json!({}), result);
+
+        // capabilities are not the sdk call, but they affect the calls the wrapper makes
+        let capabilities = self.backend.capabilities();
+        let _ = self.record(CAPABILITIES, json!({}), Ok(capabilities));
+
+        result
+    }
+
+    fn get_error_message(&mut self, code: MysticLightSdkResult) -> Result<String> {
+        let result = self.backend.get_error_message(code);
+
+        self.record("MLAPI_GetErrorMessage", json!({ "code": code }), result)
+    }
+
+    fn get_device_info(&mut self) -> Result<Vec<DeviceInfo>> {
+        let result = self.backend.get_device_info();
+
+        self.record("MLAPI_GetDeviceInfo", json!({}), result)
+    }
+
+    fn get_device_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        let result = self.backend.get_device_name(device_name);
+
+        self.record(
+            "MLAPI_GetDeviceName",
+            json!({ "device_name": device_name }),
+            result,
+        )
+    }
+
+    fn get_device_name_ex(
+        &mut self,
+        device_name: &str,
+        device_index: DeviceIndex,
+    ) -> Result<String> {
+        let result = self.backend.get_device_name_ex(device_name, device_index);
+
+        self.record(
+            "MLAPI_GetDeviceNameEx",
+            json!({ "device_name": device_name, "device_index": device_index }),
+            result,
+        )
+    }
+
+    fn get_led_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        let result = self.backend.get_led_name(device_name);
+
+        self.record(
+            "MLAPI_GetLedName",
+            json!({ "device_name": device_name }),
+            result,
+        )
+    }
+
+    fn get_led_info(&mut self, device_name: &str, led_index: LedIndex) -> Result<LedInfo> {
+        let result = self.backend.get_led_info(device_name, led_index);
+
+        self.record(
+            "MLAPI_GetLedInfo",
+            json!({ "device_name": device_name, "led_index": led_index }),
+            result,
+        )
+    }
+
+    fn get_led_max_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<BrightLevel> {
+        let result = self.backend.get_led_max_bright(device_name, led_index);
+
+        self.record(
+            "MLAPI_GetLedMaxBright",
+            json!({ "device_name": device_name, "led_index": led_index }),
+            result,
+        )
+    }
+
+    fn get_led_max_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        let result = self.backend.get_led_max_speed(device_name, led_index);
+
+        self.record(
+            "MLAPI_GetLedMaxSpeed",
+            json!({ "device_name": device_name, "led_index": led_index }),
+            result,
+        )
+    }
+
+    fn get_led_style(&mut self, device_name: &str, led_index: LedIndex) -> Result<String> {
+        let result = self.backend.get_led_style(device_name, led_index);
+
+        self.record(
+            "MLAPI_GetLedStyle",
+            json!({ "device_name": device_name, "led_index": led_index }),
+            result,
+        )
+    }
+
+    fn get_led_color(&mut self, device_name: &str, led_index: LedIndex) -> Result<Color> {
+        let result = self.backend.get_led_color(device_name, led_index);
+
+        self.record(
+            "MLAPI_GetLedColor",
+            json!({ "device_name": device_name, "led_index": led_index }),
+            result,
+        )
+    }
+
+    fn get_led_bright(&mut self, device_name: &str, led_index: LedIndex) -> Result<BrightLevel> {
+        let result = self.backend.get_led_bright(device_name, led_index);
+
+        self.record(
+            "MLAPI_GetLedBright",
+            json!({ "device_name": device_name, "led_index": led_index }),
+            result,
+        )
+    }
+
+    fn get_led_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        let result = self.backend.get_led_speed(device_name, led_index);
+
+        self.record(
+            "MLAPI_GetLedSpeed",
+            json!({ "device_name": device_name, "led_index": led_index }),
+            result,
+        )
+    }
+
+    fn set_led_style(&mut self, device_name: &str, led_index: LedIndex, style: &str) -> Result<()> {
+        let result = self.backend.set_led_style(device_name, led_index, style);
+
+        self.record(
+            "MLAPI_SetLedStyle",
+            json!({ "device_name": device_name, "led_index": led_index, "style": style }),
+            result,
+        )
+    }
+
+    fn set_led_color(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        color: &Color,
+    ) -> Result<()> {
+        let result = self.backend.set_led_color(device_name, led_index, color);
+
+        self.record(
+            "MLAPI_SetLedColor",
+            json!({ "device_name": device_name, "led_index": led_index, "color": color }),
+            result,
+        )
+    }
+
+    fn set_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()> {
+        let result = self.backend.set_led_colors(device_name, area_index, colors);
+
+        self.record(
+            "MLAPI_SetLedColors",
+            json!({ "device_name": device_name, "area_index": area_index, "colors": colors }),
+            result,
+        )
+    }
+
+    fn set_led_color_ex(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        let result =
+            self.backend
+                .set_led_color_ex(device_name, area_index, led_name, color, force_update);
+
+        self.record(
+            "MLAPI_SetLedColorEx",
+            json!({
+                "device_name": device_name,
+                "area_index": area_index,
+                "led_name": led_name,
+                "color": color,
+                "force_update": force_update,
+            }),
+            result,
+        )
+    }
+
+    fn set_led_color_sync(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        let result =
+            self.backend
+                .set_led_color_sync(device_name, area_index, led_name, color, force_update);
+
+        self.record(
+            "MLAPI_SetLedColorSync",
+            json!({
+                "device_name": device_name,
+                "area_index": area_index,
+                "led_name": led_name,
+                "color": color,
+                "force_update": force_update,
+            }),
+            result,
+        )
+    }
+
+    fn set_led_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    ) -> Result<()> {
+        let result = self.backend.set_led_bright(device_name, led_index, bright);
+
+        self.record(
+            "MLAPI_SetLedBright",
+            json!({ "device_name": device_name, "led_index": led_index, "bright": bright }),
+            result,
+        )
+    }
+
+    fn set_led_speed(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    ) -> Result<()> {
+        let result = self.backend.set_led_speed(device_name, led_index, speed);
+
+        self.record(
+            "MLAPI_SetLedSpeed",
+            json!({ "device_name": device_name, "led_index": led_index, "speed": speed }),
+            result,
+        )
+    }
+}
+
+/// Backend that serves calls from the trace recorded by the [RecordingBackend]
+///
+/// Every call is matched with the recorded entries by the function name and arguments.
+/// Entries with the same function and arguments are served in the recorded order, the last one is repeated after that.
+/// Call that was never recorded fails with [CommonError::NotRecorded]
+///
+/// # Examples
+///
+/// ```
+/// use mystic_light_sdk::{MysticLightSDK, ReplayBackend};
+///
+/// let backend = ReplayBackend::from_jsonl(r#"
+/// {"function":"MLAPI_Initialize","args":{},"result":-2,"description":"Request is timeout","timestamp":0}
+/// "#).unwrap();
+///
+/// assert!(MysticLightSDK::with_backend(backend).is_err());
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "recording")))]
+pub struct ReplayBackend {
+    entries: HashMap<(String, String), VecDeque<TraceEntry>>,
+}
+
+impl ReplayBackend {
+    /// Create backend from the recorded entries
+    pub fn new(entries: impl IntoIterator<Item = TraceEntry>) -> Self {
+        let mut grouped: HashMap<_, VecDeque<_>> = HashMap::new();
+
+        for entry in entries {
+            grouped.entry(entry.key()).or_default().push_back(entry);
+        }
+
+        Self { entries: grouped }
+    }
+
+    /// Create backend from the jsonl trace
+    pub fn from_jsonl(jsonl: &str) -> std::result::Result<Self, TraceError> {
+        let entries = jsonl
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|source| TraceError::Parse {
+                    line: index + 1,
+                    source,
+                })
+            })
+            .collect::<std::result::Result<Vec<TraceEntry>, _>>()?;
+
+        Ok(Self::new(entries))
+    }
+
+    /// Create backend from the jsonl file recorded by the [RecordingBackend]
+    pub fn from_trace_file(path: impl AsRef<Path>) -> std::result::Result<Self, TraceError> {
+        Self::from_jsonl(&fs::read_to_string(path)?)
+    }
+
+    fn replay<T: DeserializeOwned>(&mut self, function: &str, args: Value) -> Result<T> {
+        let key = (function.to_owned(), args.to_string());
+
+        let entry = match self.entries.get_mut(&key) {
+            Some(entries) if entries.len() > 1 => entries.pop_front(),
+            Some(entries) => entries.front().cloned(),
+            None => None,
+        };
+
+        let entry = entry.ok_or_else(|| CommonError::NotRecorded {
+            call: format!("{function}({args})"),
+        })?;
+
+        if let Err(source) = MysticLightSDK::parse_result(entry.result) {
+            return Err(CommonError::SdkError {
+                source,
+                description: entry.description,
+            });
+        }
+
+        serde_json::from_value(entry.output).map_err(|error| {
+            tracing::error!(function, %error, "Cannot parse recorded output");
+
+            CommonError::NotRecorded {
+                call: format!("{function}({args})"),
+            }
+        })
+    }
+}
+
+impl LightingBackend for ReplayBackend {
+    fn capabilities(&self) -> Capabilities {
+        let key = (CAPABILITIES.to_owned(), json!({}).to_string());
+
+        self.entries
+            .get(&key)
+            .and_then(|entries| entries.back())
+            .and_then(|entry| serde_json::from_value(entry.output.clone()).ok())
+            .unwrap_or_else(Capabilities::all)
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        self.replay("MLAPI_Initialize", json!({}))
+    }
+
+    fn get_error_message(&mut self, code: MysticLightSdkResult) -> Result<String> {
+        self.replay("MLAPI_GetErrorMessage", json!({ "code": code }))
+    }
+
+    fn get_device_info(&mut self) -> Result<Vec<DeviceInfo>> {
+        self.replay("MLAPI_GetDeviceInfo", json!({}))
+    }
+
+    fn get_device_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        self.replay("MLAPI_GetDeviceName", json!({ "device_name": device_name }))
+    }
+
+    fn get_device_name_ex(
+        &mut self,
+        device_name: &str,
+        device_index: DeviceIndex,
+    ) -> Result<String> {
+        self.replay(
+            "MLAPI_GetDeviceNameEx",
+            json!({ "device_name": device_name, "device_index": device_index }),
+        )
+    }
+
+    fn get_led_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        self.replay("MLAPI_GetLedName", json!({ "device_name": device_name }))
+    }
+
+    fn get_led_info(&mut self, device_name: &str, led_index: LedIndex) -> Result<LedInfo> {
+        self.replay(
+            "MLAPI_GetLedInfo",
+            json!({ "device_name": device_name, "led_index": led_index }),
+        )
+    }
+
+    fn get_led_max_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<BrightLevel> {
+        self.replay(
+            "MLAPI_GetLedMaxBright",
+            json!({ "device_name": device_name, "led_index": led_index }),
+        )
+    }
+
+    fn get_led_max_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        self.replay(
+            "MLAPI_GetLedMaxSpeed",
+            json!({ "device_name": device_name, "led_index": led_index }),
+        )
+    }
+
+    fn get_led_style(&mut self, device_name: &str, led_index: LedIndex) -> Result<String> {
+        self.replay(
+            "MLAPI_GetLedStyle",
+            json!({ "device_name": device_name, "led_index": led_index }),
+        )
+    }
+
+    fn get_led_color(&mut self, device_name: &str, led_index: LedIndex) -> Result<Color> {
+        self.replay(
+            "MLAPI_GetLedColor",
+            json!({ "device_name": device_name, "led_index": led_index }),
+        )
+    }
+
+    fn get_led_bright(&mut self, device_name: &str, led_index: LedIndex) -> Result<BrightLevel> {
+        self.replay(
+            "MLAPI_GetLedBright",
+            json!({ "device_name": device_name, "led_index": led_index }),
+        )
+    }
+
+    fn get_led_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        self.replay(
+            "MLAPI_GetLedSpeed",
+            json!({ "device_name": device_name, "led_index": led_index }),
+        )
+    }
+
+    fn set_led_style(&mut self, device_name: &str, led_index: LedIndex, style: &str) -> Result<()> {
+        self.replay(
+            "MLAPI_SetLedStyle",
+            json!({ "device_name": device_name, "led_index": led_index, "style": style }),
+        )
+    }
+
+    fn set_led_color(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        color: &Color,
+    ) -> Result<()> {
+        self.replay(
+            "MLAPI_SetLedColor",
+            json!({ "device_name": device_name, "led_index": led_index, "color": color }),
+        )
+    }
+
+    fn set_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()> {
+        self.replay(
+            "MLAPI_SetLedColors",
+            json!({ "device_name": device_name, "area_index": area_index, "colors": colors }),
+        )
+    }
+
+    fn set_led_color_ex(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        self.replay(
+            "MLAPI_SetLedColorEx",
+            json!({
+                "device_name": device_name,
+                "area_index": area_index,
+                "led_name": led_name,
+                "color": color,
+                "force_update": force_update,
+            }),
+        )
+    }
+
+    fn set_led_color_sync(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        self.replay(
+            "MLAPI_SetLedColorSync",
+            json!({
+                "device_name": device_name,
+                "area_index": area_index,
+                "led_name": led_name,
+                "color": color,
+                "force_update": force_update,
+            }),
+        )
+    }
+
+    fn set_led_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    ) -> Result<()> {
+        self.replay(
+            "MLAPI_SetLedBright",
+            json!({ "device_name": device_name, "led_index": led_index, "bright": bright }),
+        )
+    }
+
+    fn set_led_speed(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    ) -> Result<()> {
+        self.replay(
+            "MLAPI_SetLedSpeed",
+            json!({ "device_name": device_name, "led_index": led_index, "speed": speed }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LedStyle, MysticLightSDKError, UsageError};
+
+    const TRACE: &str = include_str!("../../../examples/fixtures/trace.jsonl");
+
+    #[test]
+    fn replays_recorded_trace() {
+        let sdk = MysticLightSDK::with_backend(ReplayBackend::from_jsonl(TRACE).unwrap()).unwrap();
+        let device = sdk.devices_iter().next().unwrap();
+
+        assert_eq!(device.friendly_name(), "MSI GeForce RTX 3080 GAMING X TRIO");
+
+        let led = device.led(0).unwrap();
+
+        assert_eq!(led.get_state().unwrap().style, "MSIRainbow");
+        assert!(matches!(
+            led.set_color(&Color {
+                red: 255,
+                green: 0,
+                blue: 0,
+            }),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::Timeout,
+                ..
+            })
+        ));
+        assert!(matches!(
+            led.set_style(&LedStyle::from("Unknown")),
+            Err(CommonError::UsageError {
+                source: UsageError::NotSupportedStyle { .. }
+            })
+        ));
+        assert!(matches!(
+            led.set_bright(1),
+            Err(CommonError::NotRecorded { .. })
+        ));
+    }
+
+    #[test]
+    fn lazy_metadata_errors_are_returned() {
+        // only the calls made while the lazy sdk is created are kept
+        let trace: String = TRACE
+            .lines()
+            .take_while(|line| !line.contains("MLAPI_GetDeviceNameEx"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let sdk =
+            MysticLightSDK::with_backend_lazy(ReplayBackend::from_jsonl(&trace).unwrap()).unwrap();
+        let device = sdk.devices_iter().next().unwrap();
+        let led = device.led(0).unwrap();
+
+        assert!(matches!(
+            device.try_friendly_name(),
+            Err(CommonError::NotRecorded { .. })
+        ));
+        assert_eq!(device.friendly_name(), "MSI_VGA");
+        assert!(device.try_sub_leds().is_err());
+        assert!(matches!(
+            led.try_name(),
+            Err(CommonError::NotRecorded { .. })
+        ));
+        assert!(led.try_max_bright().is_err());
+        assert!(device.led_by_name("VGA").is_err());
+        // failed fetch is not memoised
+        assert_eq!(led.name(), "");
+        assert!(!led.is_resolved());
+    }
+
+    #[test]
+    fn eager_metadata_errors_fall_back() {
+        let trace: String = TRACE
+            .lines()
+            .filter(|line| !line.contains("MLAPI_GetDeviceNameEx"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let sdk = MysticLightSDK::with_backend(ReplayBackend::from_jsonl(&trace).unwrap()).unwrap();
+        let device = sdk.devices_iter().next().unwrap();
+
+        assert_eq!(device.try_friendly_name().unwrap(), "MSI_VGA");
+        assert_eq!(device.led(0).unwrap().name(), "VGA");
+    }
+
+    #[test]
+    fn same_calls_are_served_in_order() {
+        let mut backend = ReplayBackend::from_jsonl(
+            r#"
+            {"function":"MLAPI_GetLedBright","args":{"device_name":"MSI_MB","led_index":0},"result":0,"output":1,"timestamp":0}
+            {"function":"MLAPI_GetLedBright","args":{"device_name":"MSI_MB","led_index":0},"result":0,"output":2,"timestamp":1}
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(backend.get_led_bright("MSI_MB", 0).unwrap(), 1);
+        assert_eq!(backend.get_led_bright("MSI_MB", 0).unwrap(), 2);
+        assert_eq!(backend.get_led_bright("MSI_MB", 0).unwrap(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn recorded_trace_replays_the_same_results() {
+        use crate::sdk::test_support::{simulated_backend, SharedBuffer};
+
+        fn states(sdk: &MysticLightSDK) -> Vec<(String, String)> {
+            let mut states: Vec<_> = sdk
+                .devices_iter()
+                .flat_map(|device| device.leds_iter())
+                .map(|led| (led.name().to_owned(), format!("{:?}", led.get_state())))
+                .collect();
+            states.sort();
+
+            states
+        }
+
+        let buffer = SharedBuffer::default();
+        let recorded = MysticLightSDK::with_backend(RecordingBackend::new(
+            simulated_backend(),
+            buffer.clone(),
+        ))
+        .unwrap();
+        let expected = states(&recorded);
+
+        let trace = buffer.take();
+        let replayed =
+            MysticLightSDK::with_backend(ReplayBackend::from_jsonl(&trace).unwrap()).unwrap();
+
+        assert_eq!(states(&replayed), expected);
+        assert_eq!(replayed.capabilities(), recorded.capabilities());
+    }
+}
@@ -0,0 +1,689 @@
+use std::fs;
+use std::path::Path;
+
+use custom_error::custom_error;
+
+use crate::{
+    BrightLevel, Color, CommonError, DeviceIndex, DeviceLedState, LedIndex, LedStyle,
+    MysticLightSDK, MysticLightSDKError, MysticLightSdkResult, Result, SpeedLevel,
+};
+
+use super::{Capabilities, Capability, DeviceInfo, LedInfo, LightingBackend};
+
+// result codes of the underlying sdk, see [MysticLightSDK::parse_result]
+const TIMEOUT: MysticLightSdkResult = -2;
+const NOT_INITIALIZED: MysticLightSdkResult = -4;
+const INVALID_ARGUMENT: MysticLightSdkResult = -101;
+const DEVICE_NOT_FOUND: MysticLightSdkResult = -102;
+
+custom_error! {
+  /// Errors while loading fixture for the [SimulatedBackend]
+  #[non_exhaustive]
+  pub FixtureError
+      Io{source: std::io::Error} = "Cannot read fixture file: {source}",
+      Parse{source: serde_json::Error} = "Cannot parse fixture: {source}",
+}
+
+/// Description of the simulated hardware
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SimulatedFixture {
+    /// result code returned by the initialization. Use e.g. `-2` to simulate run without admin rights
+    #[serde(default)]
+    pub initialize_result: MysticLightSdkResult,
+    /// optional sdk functions that are missing in the simulated dll. Calls to them fail with [CommonError::NotSupportedCapability]
+    #[serde(default)]
+    pub missing_capabilities: Vec<Capability>,
+    /// simulated devices
+    pub devices: Vec<SimulatedDevice>,
+}
+
+/// Single simulated device
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SimulatedDevice {
+    /// device type name e.g. `MSI_MB`
+    pub name: String,
+    /// product name of the device e.g. `MSI MPG Z690`. Device type name is used if not set
+    #[serde(default)]
+    pub friendly_name: Option<String>,
+    /// leds of the device
+    pub leds: Vec<SimulatedLed>,
+}
+
+/// Single simulated led with its mutable state
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SimulatedLed {
+    /// led name
+    pub name: String,
+    /// styles that are supported by the led
+    pub supported_styles: Vec<String>,
+    /// styles that don't support setting color. Setting color for these styles results in `Timeout` error as the real sdk does
+    #[serde(default)]
+    pub colorless_styles: Vec<String>,
+    /// styles in which reading the color of the led results in `Timeout` error e.g. to simulate the flaky hardware
+    #[serde(default)]
+    pub unreadable_styles: Vec<String>,
+    /// max brightness level
+    pub max_bright: BrightLevel,
+    /// max speed level
+    pub max_speed: SpeedLevel,
+    /// current state of the led
+    pub state: DeviceLedState,
+    /// individually addressable leds inside the led area e.g. keys of the keyboard
+    #[serde(default)]
+    pub sub_leds: Vec<SimulatedSubLed>,
+}
+
+/// Single simulated sub-led inside the led area
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SimulatedSubLed {
+    /// sub-led name
+    pub name: String,
+    /// current color of the sub-led
+    pub color: Color,
+}
+
+/// In-memory backend that simulates MSI hardware
+///
+/// Hardware is described by the [SimulatedFixture] that may be loaded from json.
+/// Backend reproduces error codes of the real sdk so the code relying on the [MysticLightSDK] might be tested without MSI hardware and Windows
+///
+/// # Examples
+///
+/// ```
+/// use mystic_light_sdk::{MysticLightSDK, SimulatedBackend};
+///
+/// let backend = SimulatedBackend::from_json(r#"{
+///     "devices": [{
+///         "name": "MSI_MB",
+///         "leds": [{
+///             "name": "JRGB1",
+///             "supported_styles": ["NoAnimation", "Rainbow"],
+///             "colorless_styles": ["Rainbow"],
+///             "max_bright": 5,
+///             "max_speed": 2,
+///             "state": { "style": "NoAnimation", "color": { "red": 255, "green": 0, "blue": 0 }, "bright": 5, "speed": 1 }
+///         }]
+///     }]
+/// }"#).unwrap();
+///
+/// let sdk = MysticLightSDK::with_backend(backend).unwrap();
+///
+/// assert_eq!(sdk.devices_iter().count(), 1);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "simulated")))]
+pub struct SimulatedBackend {
+    fixture: SimulatedFixture,
+    initialized: bool,
+}
+
+impl SimulatedBackend {
+    /// Create backend from the fixture
+    pub fn new(fixture: SimulatedFixture) -> Self {
+        Self {
+            fixture,
+            initialized: false,
+        }
+    }
+
+    /// Create backend from the json representation of the [SimulatedFixture]
+    pub fn from_json(json: &str) -> std::result::Result<Self, FixtureError> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Create backend from the json file with the [SimulatedFixture]
+    pub fn from_fixture_file(path: impl AsRef<Path>) -> std::result::Result<Self, FixtureError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /// Returns current state of the simulated hardware
+    pub fn fixture(&self) -> &SimulatedFixture {
+        &self.fixture
+    }
+
+    /// Returns error the same way as the real sdk returns non zero result code
+    fn fail<T>(code: MysticLightSdkResult) -> Result<T> {
+        let source = MysticLightSDK::parse_result(code)
+            .expect_err("non zero result code is always an error");
+
+        Err(CommonError::SdkError {
+            description: Some(Self::error_message(&source)),
+            source,
+        })
+    }
+
+    fn error_message(error: &MysticLightSDKError) -> String {
+        format!("Simulated error: {error}")
+    }
+
+    fn check_capability(&self, capability: Capability) -> Result<()> {
+        if self.fixture.missing_capabilities.contains(&capability) {
+            return Err(CommonError::NotSupportedCapability { capability });
+        }
+
+        Ok(())
+    }
+
+    fn check_initialized(&self) -> Result<()> {
+        if !self.initialized {
+            return Self::fail(NOT_INITIALIZED);
+        }
+
+        Ok(())
+    }
+
+    fn device_friendly_name(device: &SimulatedDevice) -> String {
+        device
+            .friendly_name
+            .clone()
+            .unwrap_or_else(|| device.name.clone())
+    }
+
+    /// Returns led by the sdk address.
+    ///
+    /// Leds of the multiple devices with the same type are indexed continuously across these devices.
+    /// Real sdk addressing of such devices is unknown, so [Device](crate::Device) never addresses leds past the first device of the type
+    fn led(&self, device_name: &str, led_index: LedIndex) -> Result<&SimulatedLed> {
+        self.check_initialized()?;
+
+        let mut devices = self
+            .fixture
+            .devices
+            .iter()
+            .filter(|device| device.name == device_name)
+            .peekable();
+
+        if devices.peek().is_none() {
+            return Self::fail(DEVICE_NOT_FOUND);
+        }
+
+        match devices
+            .flat_map(|device| device.leds.iter())
+            .nth(led_index as usize)
+        {
+            Some(led) => Ok(led),
+            None => Self::fail(INVALID_ARGUMENT),
+        }
+    }
+
+    fn led_mut(&mut self, device_name: &str, led_index: LedIndex) -> Result<&mut SimulatedLed> {
+        self.led(device_name, led_index)?;
+
+        let led = self
+            .fixture
+            .devices
+            .iter_mut()
+            .filter(|device| device.name == device_name)
+            .flat_map(|device| device.leds.iter_mut())
+            .nth(led_index as usize)
+            .expect("led existence is checked above");
+
+        Ok(led)
+    }
+
+    fn write_sub_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()> {
+        let led = self.led_mut(device_name, area_index)?;
+
+        if colors
+            .iter()
+            .any(|(name, _)| !led.sub_leds.iter().any(|sub_led| &sub_led.name == name))
+        {
+            return Self::fail(INVALID_ARGUMENT);
+        }
+
+        if led
+            .colorless_styles
+            .iter()
+            .any(|style| led.state.style == style.as_str())
+        {
+            return Self::fail(TIMEOUT);
+        }
+
+        for (name, color) in colors {
+            for sub_led in led
+                .sub_leds
+                .iter_mut()
+                .filter(|sub_led| &sub_led.name == name)
+            {
+                sub_led.color = color.clone();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl LightingBackend for SimulatedBackend {
+    fn capabilities(&self) -> Capabilities {
+        Capability::ALL
+            .into_iter()
+            .filter(|capability| !self.fixture.missing_capabilities.contains(capability))
+            .collect()
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        if self.fixture.initialize_result != 0 {
+            return Self::fail(self.fixture.initialize_result);
+        }
+
+        self.initialized = true;
+
+        Ok(())
+    }
+
+    fn get_error_message(&mut self, code: MysticLightSdkResult) -> Result<String> {
+        self.check_capability(Capability::GetErrorMessage)?;
+
+        match MysticLightSDK::parse_result(code) {
+            Ok(()) => Ok(String::from("No error")),
+            Err(error) => Ok(Self::error_message(&error)),
+        }
+    }
+
+    fn get_device_info(&mut self) -> Result<Vec<DeviceInfo>> {
+        self.check_initialized()?;
+
+        Ok(self
+            .fixture
+            .devices
+            .iter()
+            .map(|device| DeviceInfo {
+                name: device.name.clone(),
+                led_count: device.leds.len() as u32,
+            })
+            .collect())
+    }
+
+    fn get_device_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        self.check_capability(Capability::GetDeviceName)?;
+        self.check_initialized()?;
+
+        let names: Vec<_> = self
+            .fixture
+            .devices
+            .iter()
+            .filter(|device| device.name == device_name)
+            .map(Self::device_friendly_name)
+            .collect();
+
+        if names.is_empty() {
+            return Self::fail(DEVICE_NOT_FOUND);
+        }
+
+        Ok(names)
+    }
+
+    fn get_device_name_ex(
+        &mut self,
+        device_name: &str,
+        device_index: DeviceIndex,
+    ) -> Result<String> {
+        self.check_capability(Capability::GetDeviceNameEx)?;
+        self.check_initialized()?;
+
+        let names: Vec<_> = self
+            .fixture
+            .devices
+            .iter()
+            .filter(|device| device.name == device_name)
+            .map(Self::device_friendly_name)
+            .collect();
+
+        if names.is_empty() {
+            return Self::fail(DEVICE_NOT_FOUND);
+        }
+
+        match names.into_iter().nth(device_index as usize) {
+            Some(name) => Ok(name),
+            None => Self::fail(INVALID_ARGUMENT),
+        }
+    }
+
+    fn get_led_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        self.check_capability(Capability::GetLedName)?;
+        self.check_initialized()?;
+
+        let mut devices = self
+            .fixture
+            .devices
+            .iter()
+            .filter(|device| device.name == device_name)
+            .peekable();
+
+        if devices.peek().is_none() {
+            return Self::fail(DEVICE_NOT_FOUND);
+        }
+
+        Ok(devices
+            .flat_map(|device| device.leds.iter())
+            .flat_map(|led| led.sub_leds.iter())
+            .map(|sub_led| sub_led.name.clone())
+            .collect())
+    }
+
+    fn get_led_info(&mut self, device_name: &str, led_index: LedIndex) -> Result<LedInfo> {
+        let led = self.led(device_name, led_index)?;
+
+        Ok(LedInfo {
+            name: led.name.clone(),
+            supported_styles: led.supported_styles.clone(),
+        })
+    }
+
+    fn get_led_max_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<BrightLevel> {
+        Ok(self.led(device_name, led_index)?.max_bright)
+    }
+
+    fn get_led_max_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        Ok(self.led(device_name, led_index)?.max_speed)
+    }
+
+    fn get_led_style(&mut self, device_name: &str, led_index: LedIndex) -> Result<String> {
+        Ok(self.led(device_name, led_index)?.state.style.to_string())
+    }
+
+    fn get_led_color(&mut self, device_name: &str, led_index: LedIndex) -> Result<Color> {
+        let led = self.led(device_name, led_index)?;
+
+        if led
+            .unreadable_styles
+            .iter()
+            .any(|style| led.state.style == style.as_str())
+        {
+            return Self::fail(TIMEOUT);
+        }
+
+        Ok(led.state.color.clone())
+    }
+
+    fn get_led_bright(&mut self, device_name: &str, led_index: LedIndex) -> Result<BrightLevel> {
+        Ok(self.led(device_name, led_index)?.state.bright)
+    }
+
+    fn get_led_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        Ok(self.led(device_name, led_index)?.state.speed)
+    }
+
+    fn set_led_style(&mut self, device_name: &str, led_index: LedIndex, style: &str) -> Result<()> {
+        let led = self.led_mut(device_name, led_index)?;
+
+        if !led.supported_styles.iter().any(|s| s == style) {
+            return Self::fail(INVALID_ARGUMENT);
+        }
+
+        led.state.style = LedStyle::from(style);
+
+        Ok(())
+    }
+
+    fn set_led_color(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        color: &Color,
+    ) -> Result<()> {
+        let led = self.led_mut(device_name, led_index)?;
+
+        if led
+            .colorless_styles
+            .iter()
+            .any(|style| led.state.style == style.as_str())
+        {
+            return Self::fail(TIMEOUT);
+        }
+
+        led.state.color = color.clone();
+
+        Ok(())
+    }
+
+    fn set_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()> {
+        self.check_capability(Capability::SetLedColors)?;
+
+        self.write_sub_led_colors(device_name, area_index, colors)
+    }
+
+    fn set_led_color_ex(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        _force_update: bool,
+    ) -> Result<()> {
+        self.check_capability(Capability::SetLedColorEx)?;
+
+        self.write_sub_led_colors(
+            device_name,
+            area_index,
+            &[(led_name.to_owned(), color.clone())],
+        )
+    }
+
+    fn set_led_color_sync(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        _force_update: bool,
+    ) -> Result<()> {
+        self.check_capability(Capability::SetLedColorSync)?;
+
+        self.write_sub_led_colors(
+            device_name,
+            area_index,
+            &[(led_name.to_owned(), color.clone())],
+        )
+    }
+
+    fn set_led_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    ) -> Result<()> {
+        let led = self.led_mut(device_name, led_index)?;
+
+        if bright > led.max_bright {
+            return Self::fail(INVALID_ARGUMENT);
+        }
+
+        led.state.bright = bright;
+
+        Ok(())
+    }
+
+    fn set_led_speed(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    ) -> Result<()> {
+        let led = self.led_mut(device_name, led_index)?;
+
+        if speed > led.max_speed {
+            return Self::fail(INVALID_ARGUMENT);
+        }
+
+        led.state.speed = speed;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk::test_support::{
+        find_led, simulated_backend, simulated_sdk, simulated_sdk_without,
+    };
+
+    #[test]
+    fn resolves_devices_from_fixture() {
+        let sdk = simulated_sdk();
+
+        let names: Vec<_> = sdk.devices_iter().map(|device| device.name()).collect();
+
+        assert_eq!(names, vec!["MSI_MB", "MSI_VGA", "MSI_KEYBOARD"]);
+
+        let led = find_led(&sdk, "JRAINBOW1");
+
+        assert_eq!(led.max_bright(), 5);
+        assert_eq!(led.max_speed(), 2);
+        assert_eq!(
+            led.supported_styles(),
+            ["NoAnimation", "Breathing", "Flashing", "Rainbow"]
+        );
+    }
+
+    #[test]
+    fn set_state_updates_led() {
+        let sdk = simulated_sdk();
+        let led = find_led(&sdk, "JRGB1");
+
+        let state = DeviceLedState {
+            style: LedStyle::Breathing,
+            color: Color {
+                red: 1,
+                green: 2,
+                blue: 3,
+            },
+            bright: 1,
+            speed: 0,
+        };
+
+        led.set_state(&state).unwrap();
+
+        assert_eq!(led.get_state().unwrap(), state);
+    }
+
+    #[test]
+    fn set_color_for_colorless_style_is_timeout() {
+        let sdk = simulated_sdk();
+        let led = find_led(&sdk, "VGA");
+
+        let result = led.set_color(&Color {
+            red: 1,
+            green: 2,
+            blue: 3,
+        });
+
+        assert!(matches!(
+            result,
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::Timeout,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn errors_carry_code_and_description() {
+        let backend = SimulatedBackend::new(SimulatedFixture {
+            initialize_result: -999,
+            ..Default::default()
+        });
+
+        match MysticLightSDK::with_backend(backend) {
+            Err(CommonError::SdkError {
+                source,
+                description,
+            }) => {
+                assert!(matches!(
+                    source,
+                    MysticLightSDKError::Unknown { code: -999 }
+                ));
+                assert_eq!(source.code(), -999);
+                assert_eq!(
+                    description.as_deref(),
+                    Some("Simulated error: Unknown error with code -999")
+                );
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn initialize_result_is_propagated() {
+        let backend = SimulatedBackend::new(SimulatedFixture {
+            initialize_result: -2,
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            MysticLightSDK::with_backend(backend),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::Timeout,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn calls_reproduce_sdk_errors() {
+        let mut backend = simulated_backend();
+
+        assert!(matches!(
+            backend.get_device_info(),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::NotInitialized,
+                ..
+            })
+        ));
+
+        backend.initialize().unwrap();
+
+        assert!(matches!(
+            backend.get_led_style("MSI_MOUSE", 0),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::DeviceNotFound,
+                ..
+            })
+        ));
+        assert!(matches!(
+            backend.get_led_style("MSI_MB", 10),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::InvalidArgument,
+                ..
+            })
+        ));
+        assert!(matches!(
+            backend.set_led_bright("MSI_MB", 0, 100),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::InvalidArgument,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn reports_capabilities() {
+        let sdk = simulated_sdk_without(vec![]);
+
+        assert_eq!(sdk.capabilities(), &Capabilities::all());
+
+        let sdk = simulated_sdk_without(vec![Capability::SetLedColors]);
+
+        assert!(!sdk.capabilities().supports(Capability::SetLedColors));
+        assert!(sdk.capabilities().supports(Capability::SetLedColorEx));
+        assert_eq!(
+            sdk.capabilities().missing().collect::<Vec<_>>(),
+            vec![Capability::SetLedColors]
+        );
+    }
+}
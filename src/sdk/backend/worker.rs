@@ -0,0 +1,410 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+#[cfg(windows)]
+use super::LibraryBackend;
+use super::{Capabilities, DeviceInfo, LedInfo, LightingBackend};
+use crate::sdk::task::reply_channel;
+use crate::{
+    BrightLevel, Color, CommonError, DeviceIndex, LedIndex, MysticLightSdkResult, Result,
+    SpeedLevel, SyncError,
+};
+
+const WORKER_THREAD_NAME: &str = "mystic-light-sdk";
+
+type Command = Box<dyn FnOnce(&mut dyn LightingBackend) + Send>;
+
+/// Handle to the backend that is owned by the dedicated worker thread
+///
+/// All of the backend calls are executed on the single worker thread in the order they were sent.
+/// This gives thread affinity for the underlying sdk (it uses COM types like BSTR and SAFEARRAY) and strict ordering of the calls.
+/// Handle is cheap to clone and every clone sends commands to the same worker. Worker stops when all of the handles are dropped.
+///
+/// Handle implements [LightingBackend] itself, so it may be passed to the [MysticLightSDK::with_backend](crate::MysticLightSDK::with_backend)
+///
+/// # Examples
+///
+/// ```ignore
+/// use mystic_light_sdk::{MysticLightSDK, SdkHandle};
+///
+/// let handle = SdkHandle::with_library("sdk/MysticLight_SDK_x64.dll")?;
+/// let sdk = MysticLightSDK::with_backend(handle)?;
+/// ```
+#[derive(Clone)]
+pub struct SdkHandle {
+    sender: Sender<Command>,
+    capabilities: Capabilities,
+}
+
+impl Debug for SdkHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SdkHandle")
+            .field("capabilities", &self.capabilities)
+            .finish()
+    }
+}
+
+impl SdkHandle {
+    /// Spawn the worker thread and create the backend on it with the passed function
+    ///
+    /// Backend is created on the worker thread, so it never leaves that thread
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn spawn<B, F>(create_backend: F) -> Result<Self>
+    where
+        B: LightingBackend + 'static,
+        F: FnOnce() -> Result<B> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<Command>();
+        let (init_sender, init_receiver) = mpsc::sync_channel(1);
+
+        thread::Builder::new()
+            .name(WORKER_THREAD_NAME.to_owned())
+            .spawn(move || {
+                let mut backend = match create_backend() {
+                    Ok(backend) => backend,
+                    Err(error) => {
+                        let _ = init_sender.send(Err(error));
+
+                        return;
+                    }
+                };
+
+                if init_sender.send(Ok(backend.capabilities())).is_err() {
+                    return;
+                }
+
+                tracing::debug!("Sdk worker is started");
+
+                while let Ok(command) = receiver.recv() {
+                    command(&mut backend);
+                }
+
+                tracing::debug!("Sdk worker is stopped");
+            })
+            .map_err(|error| {
+                tracing::error!(%error, "Cannot spawn sdk worker thread");
+
+                CommonError::from(SyncError::Disconnected)
+            })?;
+
+        let capabilities = init_receiver
+            .recv()
+            .map_err(|_| SyncError::Disconnected)??;
+
+        Ok(Self {
+            sender,
+            capabilities,
+        })
+    }
+
+    /// Spawn the worker thread that loads the dll file by the passed path, see [LibraryBackend::new]
+    #[cfg(windows)]
+    pub fn with_library(lib_path: &str) -> Result<Self> {
+        let lib_path = lib_path.to_owned();
+
+        Self::spawn(move || LibraryBackend::new(&lib_path))
+    }
+
+    /// Execute the function with the backend on the worker thread and wait for its result
+    ///
+    /// Returns `Err(SyncError::Disconnected)` if the worker is stopped e.g. because of the panic inside the previous command
+    pub fn call<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn LightingBackend) -> Result<T> + Send + 'static,
+    {
+        let (reply_sender, reply_receiver) = mpsc::sync_channel(1);
+
+        self.sender
+            .send(Box::new(move |backend| {
+                let _ = reply_sender.send(f(backend));
+            }))
+            .map_err(|_| SyncError::Disconnected)?;
+
+        reply_receiver.recv().map_err(|_| SyncError::Disconnected)?
+    }
+
+    /// Execute the function with the backend on the worker thread. Returned future resolves with its result and may be awaited by any async runtime
+    pub fn call_async<T, F>(&self, f: F) -> impl Future<Output = Result<T>> + Send + 'static
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn LightingBackend) -> Result<T> + Send + 'static,
+    {
+        let (reply_sender, reply) = reply_channel();
+
+        // if the worker is stopped the command is dropped with the reply sender and the reply resolves with an error
+        let _ = self
+            .sender
+            .send(Box::new(move |backend| reply_sender.send(f(backend))));
+
+        reply
+    }
+}
+
+impl LightingBackend for SdkHandle {
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.clone()
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        self.call(|backend| backend.initialize())
+    }
+
+    fn get_error_message(&mut self, code: MysticLightSdkResult) -> Result<String> {
+        self.call(move |backend| backend.get_error_message(code))
+    }
+
+    fn get_device_info(&mut self) -> Result<Vec<DeviceInfo>> {
+        self.call(|backend| backend.get_device_info())
+    }
+
+    fn get_device_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_device_name(&device_name))
+    }
+
+    fn get_device_name_ex(
+        &mut self,
+        device_name: &str,
+        device_index: DeviceIndex,
+    ) -> Result<String> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_device_name_ex(&device_name, device_index))
+    }
+
+    fn get_led_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_name(&device_name))
+    }
+
+    fn get_led_info(&mut self, device_name: &str, led_index: LedIndex) -> Result<LedInfo> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_info(&device_name, led_index))
+    }
+
+    fn get_led_max_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<BrightLevel> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_max_bright(&device_name, led_index))
+    }
+
+    fn get_led_max_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_max_speed(&device_name, led_index))
+    }
+
+    fn get_led_style(&mut self, device_name: &str, led_index: LedIndex) -> Result<String> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_style(&device_name, led_index))
+    }
+
+    fn get_led_color(&mut self, device_name: &str, led_index: LedIndex) -> Result<Color> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_color(&device_name, led_index))
+    }
+
+    fn get_led_bright(&mut self, device_name: &str, led_index: LedIndex) -> Result<BrightLevel> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_bright(&device_name, led_index))
+    }
+
+    fn get_led_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.get_led_speed(&device_name, led_index))
+    }
+
+    fn set_led_style(&mut self, device_name: &str, led_index: LedIndex, style: &str) -> Result<()> {
+        let device_name = device_name.to_owned();
+        let style = style.to_owned();
+
+        self.call(move |backend| backend.set_led_style(&device_name, led_index, &style))
+    }
+
+    fn set_led_color(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        color: &Color,
+    ) -> Result<()> {
+        let device_name = device_name.to_owned();
+        let color = color.clone();
+
+        self.call(move |backend| backend.set_led_color(&device_name, led_index, &color))
+    }
+
+    fn set_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()> {
+        let device_name = device_name.to_owned();
+        let colors = colors.to_vec();
+
+        self.call(move |backend| backend.set_led_colors(&device_name, area_index, &colors))
+    }
+
+    fn set_led_color_ex(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        let device_name = device_name.to_owned();
+        let led_name = led_name.to_owned();
+        let color = color.clone();
+
+        self.call(move |backend| {
+            backend.set_led_color_ex(&device_name, area_index, &led_name, &color, force_update)
+        })
+    }
+
+    fn set_led_color_sync(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        let device_name = device_name.to_owned();
+        let led_name = led_name.to_owned();
+        let color = color.clone();
+
+        self.call(move |backend| {
+            backend.set_led_color_sync(&device_name, area_index, &led_name, &color, force_update)
+        })
+    }
+
+    fn set_led_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    ) -> Result<()> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.set_led_bright(&device_name, led_index, bright))
+    }
+
+    fn set_led_speed(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    ) -> Result<()> {
+        let device_name = device_name.to_owned();
+
+        self.call(move |backend| backend.set_led_speed(&device_name, led_index, speed))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "simulated")]
+mod tests {
+    use super::*;
+    use crate::sdk::task::block_on;
+    use crate::sdk::test_support::{find_led, simulated_backend};
+    use crate::{MysticLightSDK, SimulatedBackend};
+
+    fn spawn() -> SdkHandle {
+        SdkHandle::spawn(|| Ok(simulated_backend())).unwrap()
+    }
+
+    #[test]
+    fn calls_are_executed_on_the_worker_thread() {
+        let handle = spawn();
+
+        let thread_name = handle
+            .call(|_| Ok(thread::current().name().map(String::from)))
+            .unwrap();
+
+        assert_eq!(thread_name.as_deref(), Some(WORKER_THREAD_NAME));
+    }
+
+    #[test]
+    fn sdk_works_through_the_handle() {
+        let handle = spawn();
+        let sdk = MysticLightSDK::with_backend(handle.clone()).unwrap();
+
+        let writes = [
+            ("MSI_MB", 0, "JRAINBOW1", 1),
+            ("MSI_MB", 1, "JRGB1", 2),
+            ("MSI_VGA", 0, "VGA", 3),
+            ("MSI_KEYBOARD", 0, "Keyboard", 4),
+        ];
+
+        let threads: Vec<_> = writes
+            .iter()
+            .map(|&(device_name, led_index, _, bright)| {
+                let mut handle = handle.clone();
+
+                thread::spawn(move || handle.set_led_bright(device_name, led_index, bright))
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap().unwrap();
+        }
+
+        for (_, _, led_name, bright) in writes {
+            assert_eq!(find_led(&sdk, led_name).get_state().unwrap().bright, bright);
+        }
+    }
+
+    #[test]
+    fn async_calls_are_executed_on_the_worker_thread() {
+        let handle = spawn();
+
+        let future = handle.call_async(|backend| {
+            backend.initialize()?;
+            backend.get_device_info()
+        });
+
+        assert_eq!(block_on(future).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn backend_creation_error_is_returned() {
+        let result =
+            SdkHandle::spawn(|| -> Result<SimulatedBackend> { Err(SyncError::Poison.into()) });
+
+        assert!(matches!(
+            result,
+            Err(CommonError::SyncError {
+                source: SyncError::Poison
+            })
+        ));
+    }
+
+    #[test]
+    fn panic_stops_the_worker() {
+        let handle = spawn();
+
+        let _ = handle.call(|_| -> Result<()> { panic!("command failed") });
+
+        assert!(matches!(
+            handle.call(|backend| backend.get_device_info()),
+            Err(CommonError::SyncError {
+                source: SyncError::Disconnected
+            })
+        ));
+    }
+}
@@ -0,0 +1,238 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use custom_error::custom_error;
+
+use crate::backend::DeviceInfo;
+use crate::{BrightLevel, CommonError, Device, DeviceIndex, LedStyle, SpeedLevel};
+
+custom_error! {
+  /// Errors while using the [InventoryCache]
+  #[non_exhaustive]
+  pub CacheError
+      Io{source: io::Error} = "Cannot access cache file: {source}",
+      Parse{source: serde_json::Error} = "Cannot parse cache: {source}",
+      /// Devices cannot be resolved from the sdk to revalidate the cache
+      Sdk{source: CommonError} = "Cannot resolve devices: {source}",
+}
+
+/// returns signature of the connected hardware e.g. `MSI_MB:5;MSI_VGA:2`.
+/// Signature changes whenever devices are added, removed, reordered or change their led count
+pub fn hardware_signature(devices: &[DeviceInfo]) -> String {
+    let devices: Vec<_> = devices
+        .iter()
+        .map(|device| format!("{}:{}", device.name, device.led_count))
+        .collect();
+
+    devices.join(";")
+}
+
+/// Static info of the single led that is stored in the cache
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InventoryLed {
+    pub name: String,
+    pub supported_styles: Vec<LedStyle>,
+    pub max_bright: BrightLevel,
+    pub max_speed: SpeedLevel,
+}
+
+/// Static info of the single device that is stored in the cache
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InventoryDevice {
+    /// device type name e.g. `MSI_MB`
+    pub name: String,
+    pub friendly_name: String,
+    /// index of the device among devices with the same type
+    pub index: DeviceIndex,
+    pub sub_leds: Vec<String>,
+    /// leds ordered by their index inside the device
+    pub leds: Vec<InventoryLed>,
+}
+
+/// Snapshot of the devices and their leds. Enough to create [MysticLightSDK](crate::MysticLightSDK) without querying every led from the sdk
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Inventory {
+    /// signature of the hardware the inventory was taken from, see [hardware_signature]
+    pub signature: String,
+    /// devices in the order they are enumerated by the sdk
+    pub devices: Vec<InventoryDevice>,
+}
+
+impl Inventory {
+    /// Creates inventory from the resolved devices
+    ///
+    /// Metadata of the lazy devices and leds is fetched if it is not fetched yet. Returns error if it cannot be fetched,
+    /// so the inventory never contains placeholders
+    pub fn from_devices(devices: &[Device]) -> Result<Self, CommonError> {
+        let devices_info: Vec<_> = devices
+            .iter()
+            .map(|device| DeviceInfo {
+                name: device.name().to_owned(),
+                led_count: device.leds().len() as u32,
+            })
+            .collect();
+
+        let devices = devices
+            .iter()
+            .map(|device| {
+                Ok(InventoryDevice {
+                    name: device.name().to_owned(),
+                    friendly_name: device.try_friendly_name()?.to_owned(),
+                    index: device.index(),
+                    sub_leds: device.try_sub_leds()?.to_vec(),
+                    leds: device
+                        .leds_iter()
+                        .map(|led| {
+                            Ok(InventoryLed {
+                                name: led.try_name()?.to_owned(),
+                                supported_styles: led.try_supported_styles()?.to_vec(),
+                                max_bright: led.try_max_bright()?,
+                                max_speed: led.try_max_speed()?,
+                            })
+                        })
+                        .collect::<Result<_, CommonError>>()?,
+                })
+            })
+            .collect::<Result<_, CommonError>>()?;
+
+        Ok(Self {
+            signature: hardware_signature(&devices_info),
+            devices,
+        })
+    }
+}
+
+/// Persistent json cache of the [Inventory]
+///
+/// Clones share the same file and writes through them never interleave
+#[derive(Debug, Clone)]
+pub struct InventoryCache {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl InventoryCache {
+    /// Creates cache stored in the passed file. File is created on the first write
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Arc::default(),
+        }
+    }
+
+    /// returns path to the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// returns cached inventory or `None` if nothing is cached yet
+    #[tracing::instrument(level = "debug", skip(self), fields(path = ?self.path))]
+    pub fn load(&self) -> Result<Option<Inventory>, CacheError> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Replace cached inventory. File is replaced atomically, so readers never see partially written cache
+    #[tracing::instrument(level = "debug", skip_all, fields(path = ?self.path))]
+    pub fn store(&self, inventory: &Inventory) -> Result<(), CacheError> {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+
+        fs::write(&temp_path, serde_json::to_vec_pretty(inventory)?)?;
+        fs::rename(&temp_path, &self.path)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use super::*;
+    use crate::sdk::test_support::simulated_backend;
+    use crate::MysticLightSDK;
+
+    fn temp_cache(name: &str) -> InventoryCache {
+        let path = std::env::temp_dir().join(format!(
+            "mystic-light-sdk-{}-{name}.json",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        InventoryCache::new(path)
+    }
+
+    #[test]
+    fn missing_cache_is_filled_and_used_afterwards() {
+        let cache = temp_cache("fill");
+
+        assert!(cache.load().unwrap().is_none());
+
+        let sdk = MysticLightSDK::with_backend_cached(simulated_backend(), cache.clone()).unwrap();
+        let mut inventory = cache.load().unwrap().unwrap();
+
+        assert_eq!(inventory.signature, "MSI_MB:2;MSI_VGA:1;MSI_KEYBOARD:1");
+        assert_eq!(inventory, Inventory::from_devices(sdk.devices()).unwrap());
+
+        // stale cache with the same signature is used as is until it is revalidated
+        inventory.devices[0].leds[0].max_bright = 1;
+        cache.store(&inventory).unwrap();
+
+        let sdk = MysticLightSDK::with_backend_cached(simulated_backend(), cache.clone()).unwrap();
+
+        assert_eq!(sdk.devices()[0].leds()[0].max_bright(), 1);
+        assert_eq!(sdk.devices()[1].leds()[0].name(), "VGA");
+
+        let revalidation = sdk.spawn_cache_revalidation(cache.clone()).unwrap();
+
+        assert!(revalidation.join().unwrap().unwrap());
+        assert_eq!(
+            cache.load().unwrap().unwrap().devices[0].leds[0].max_bright,
+            5
+        );
+        assert!(!sdk.revalidate_cache(&cache).unwrap());
+
+        fs::remove_file(cache.path()).unwrap();
+    }
+
+    #[test]
+    fn cache_of_another_hardware_is_replaced() {
+        let cache = temp_cache("replace");
+
+        cache
+            .store(&Inventory {
+                signature: String::from("MSI_MB:1"),
+                devices: Vec::new(),
+            })
+            .unwrap();
+
+        let sdk = MysticLightSDK::with_backend_cached(simulated_backend(), cache.clone()).unwrap();
+
+        assert_eq!(sdk.devices().len(), 3);
+        assert_eq!(
+            cache.load().unwrap().unwrap(),
+            Inventory::from_devices(sdk.devices()).unwrap()
+        );
+
+        fs::write(cache.path(), "not a json").unwrap();
+
+        assert!(matches!(cache.load(), Err(CacheError::Parse { .. })));
+        assert!(MysticLightSDK::with_backend_cached(simulated_backend(), cache.clone()).is_ok());
+
+        fs::remove_file(cache.path()).unwrap();
+    }
+}
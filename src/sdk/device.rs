@@ -1,61 +1,130 @@
-#[cfg(feature = "async-graphql")]
-use either::Either;
-use libloading::Library;
-use std::collections::HashMap;
-use std::fmt::Debug;
-use std::sync::{Arc, Mutex};
+use std::fmt::{Debug, Display};
+use std::sync::OnceLock;
 
 #[cfg(feature = "async-graphql")]
 use super::led::DeviceLedMutation;
 
-use super::led::DeviceLed;
-use super::types::{Filter, Result};
+use super::backend::{Capability, SharedBackend};
+#[cfg(feature = "cache")]
+use super::cache::InventoryDevice;
+use super::device_kind::DeviceKind;
+use super::led::{DeviceLed, LedResolution};
+use super::types::{DeviceIndex, LedIndex, Result};
 
 /// used for filtering device's leds.
 /// Currently, supports only filtering by name
-#[derive(Default)]
-#[cfg_attr(feature = "async-graphql", derive(async_graphql::InputObject))]
+#[cfg(feature = "async-graphql")]
+#[derive(Default, async_graphql::InputObject)]
 struct DeviceLedFilter {
     names: Option<Vec<String>>,
 }
 
-impl Filter<&DeviceLed> for DeviceLedFilter {
-    fn predicate(&self, led: &DeviceLed) -> bool {
+#[cfg(feature = "async-graphql")]
+impl DeviceLedFilter {
+    /// returns whether the led passes the filter or error if the name of the lazy led cannot be fetched
+    fn predicate(&self, led: &DeviceLed) -> Result<bool> {
         match &self.names {
             Some(names) => {
                 if names.is_empty() {
-                    return true;
+                    return Ok(true);
                 }
 
-                names.iter().any(|name| name == led.name())
+                let led_name = led.try_name()?;
+
+                Ok(names.iter().any(|name| name == led_name))
             }
-            None => true,
+            None => Ok(true),
         }
     }
 }
 
 #[cfg(feature = "async-graphql")]
-fn filter_leds(
-    leds: &HashMap<String, DeviceLed>,
-    filter: DeviceLedFilter,
-) -> Either<impl Iterator<Item = &DeviceLed>, impl Iterator<Item = &DeviceLed>> {
-    match filter.names {
-        Some(names) => Either::Left(names.into_iter().filter_map(|led_name| leds.get(&led_name))),
-        None => Either::Right(leds.values()),
+fn filter_leds(leds: &[DeviceLed], filter: DeviceLedFilter) -> Result<Vec<&DeviceLed>> {
+    let mut filtered = Vec::new();
+
+    for led in leds {
+        if filter.predicate(led)? {
+            filtered.push(led);
+        }
+    }
+
+    Ok(filtered)
+}
+
+/// Unique identifier of the device
+///
+/// Multiple devices of the same type (e.g. two `MSI_VGA`) are distinguished by the index among devices of that type.
+/// Only the first device of the type has leds, see [Device::leds]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "async-graphql",
+    derive(async_graphql::SimpleObject, async_graphql::InputObject),
+    graphql(input_name = "DeviceIdInput")
+)]
+pub struct DeviceId {
+    /// device type name e.g. `MSI_VGA`
+    pub name: String,
+    /// index of the device among devices with the same type
+    pub index: DeviceIndex,
+}
+
+impl DeviceId {
+    /// Creates identifier of the device with the passed type name and index among devices of that type
+    pub fn new(name: impl Into<String>, index: DeviceIndex) -> Self {
+        Self {
+            name: name.into(),
+            index,
+        }
+    }
+}
+
+impl Display for DeviceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.name, self.index)
     }
 }
 
 /// Represents single hardware MysticLight Device
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Device {
     name: String,
+    kind: DeviceKind,
+    index: DeviceIndex,
+    /// fetched on the first access in the lazy mode, see [LedResolution::Lazy]
+    friendly_name: OnceLock<String>,
+    /// fetched on the first access in the lazy mode, see [LedResolution::Lazy]
+    sub_leds: OnceLock<Vec<String>>,
 
-    pub(crate) leds: HashMap<String, DeviceLed>,
+    pub(crate) leds: Vec<DeviceLed>,
 
-    #[cfg_attr(feature = "serde", serde(skip))]
-    library: Arc<Mutex<Library>>,
-    #[cfg_attr(feature = "serde", serde(skip))]
+    // internal fields that required to make api calls
+    backend: SharedBackend,
     led_count: u32,
+    shared_type: bool,
+    resolution: LedResolution,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Device {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+
+        let mut device = serializer.serialize_struct("Device", 6)?;
+
+        device.serialize_field("name", &self.name)?;
+        device.serialize_field("kind", &self.kind)?;
+        device.serialize_field(
+            "friendly_name",
+            self.try_friendly_name().map_err(S::Error::custom)?,
+        )?;
+        device.serialize_field("index", &self.index)?;
+        device.serialize_field("sub_leds", self.try_sub_leds().map_err(S::Error::custom)?)?;
+        device.serialize_field("leds", &self.leds)?;
+        device.end()
+    }
 }
 
 /// Represents single hardware MysticLight Device
@@ -67,9 +136,39 @@ impl Device {
         self.name()
     }
 
+    #[graphql(name = "kind")]
+    async fn async_graphql_kind(&self) -> &DeviceKind {
+        self.kind()
+    }
+
+    #[graphql(name = "friendlyName")]
+    async fn async_graphql_friendly_name(&self) -> Result<&str> {
+        self.try_friendly_name()
+    }
+
+    #[graphql(name = "index")]
+    async fn async_graphql_index(&self) -> DeviceIndex {
+        self.index()
+    }
+
+    /// returns names of the individually addressable sub-leds of the device
+    #[graphql(name = "subLeds")]
+    async fn async_graphql_sub_leds(&self) -> Result<&[String]> {
+        self.try_sub_leds()
+    }
+
+    #[graphql(name = "id")]
+    async fn async_graphql_id(&self) -> DeviceId {
+        self.id()
+    }
+
     /// returns device's leds
-    async fn leds(&self, #[graphql(default)] filter: DeviceLedFilter) -> Vec<&DeviceLed> {
-        filter_leds(&self.leds, filter).collect()
+    #[graphql(name = "leds")]
+    async fn async_graphql_leds(
+        &self,
+        #[graphql(default)] filter: DeviceLedFilter,
+    ) -> Result<Vec<&DeviceLed>> {
+        filter_leds(&self.leds, filter)
     }
 }
 
@@ -83,10 +182,14 @@ pub struct DeviceMutation<'a>(pub &'a Device);
 #[async_graphql::Object]
 impl<'a> DeviceMutation<'a> {
     /// returns device's leds wrapped in mutation wrapper
-    async fn leds(&self, #[graphql(default)] filter: DeviceLedFilter) -> Vec<DeviceLedMutation> {
-        filter_leds(&self.0.leds, filter)
+    async fn leds(
+        &self,
+        #[graphql(default)] filter: DeviceLedFilter,
+    ) -> Result<Vec<DeviceLedMutation<'_>>> {
+        Ok(filter_leds(&self.0.leds, filter)?
+            .into_iter()
             .map(DeviceLedMutation)
-            .collect()
+            .collect())
     }
 }
 
@@ -94,55 +197,383 @@ impl Debug for Device {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Device")
             .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("friendly_name", &self.friendly_name.get())
+            .field("index", &self.index)
+            .field("sub_leds", &self.sub_leds.get())
             .field("led_count", &self.led_count)
             .finish()
     }
 }
 
 impl Device {
+    /// returns device type name e.g. `MSI_MB`
     pub fn name(&self) -> &str {
         &self.name
     }
 
-    #[tracing::instrument(level = "debug", skip(library))]
-    pub(crate) fn new(library: Arc<Mutex<Library>>, name: String, led_count: u32) -> Result<Self> {
-        let leds = Self::resolve_leds(&library, &name, led_count)?;
+    /// returns category of the device parsed from its type name
+    pub fn kind(&self) -> &DeviceKind {
+        &self.kind
+    }
+
+    /// returns human-readable product name of the device e.g. `MSI MPG Z690`.
+    /// Falls back to the device type name if the backend cannot resolve product names or fails to fetch the name of the eager device.
+    ///
+    /// Name of the lazy device falls back to the type name if it cannot be fetched as well. The error is logged and the name
+    /// is fetched again on the next access, use [Device::try_friendly_name] to handle it
+    pub fn friendly_name(&self) -> &str {
+        self.try_friendly_name()
+            .map_err(|error| {
+                tracing::warn!(name = self.name, index = self.index, %error, "Cannot fetch device friendly name");
+            })
+            .unwrap_or(&self.name)
+    }
+
+    /// returns human-readable product name of the device or error if the name of the lazy device cannot be fetched
+    pub fn try_friendly_name(&self) -> Result<&str> {
+        if let Some(friendly_name) = self.friendly_name.get() {
+            return Ok(friendly_name);
+        }
+
+        let friendly_name = Self::fetch_friendly_name(&self.backend, &self.name, self.index)?;
+
+        Ok(self.friendly_name.get_or_init(|| friendly_name))
+    }
+
+    /// returns index of the device among devices with the same type
+    pub fn index(&self) -> DeviceIndex {
+        self.index
+    }
+
+    /// returns names of the individually addressable sub-leds of the device e.g. keys of the keyboard.
+    ///
+    /// Sdk reports sub-leds per device type, so the list is empty if there are multiple devices with the same type
+    /// as the sub-leds cannot be attributed to the single device
+    ///
+    /// Sub-leds are empty if they cannot be fetched, see [Device::friendly_name]
+    pub fn sub_leds(&self) -> &[String] {
+        self.try_sub_leds()
+            .map_err(|error| {
+                tracing::warn!(name = self.name, index = self.index, %error, "Cannot fetch device sub-leds");
+            })
+            .unwrap_or_default()
+    }
+
+    /// returns names of the individually addressable sub-leds of the device or error if they cannot be fetched for the lazy device
+    pub fn try_sub_leds(&self) -> Result<&[String]> {
+        if let Some(sub_leds) = self.sub_leds.get() {
+            return Ok(sub_leds);
+        }
+
+        let sub_leds = Self::fetch_sub_leds(&self.backend, &self.name, self.shared_type)?;
+
+        Ok(self.sub_leds.get_or_init(|| sub_leds))
+    }
+
+    /// returns unique identifier of the device
+    pub fn id(&self) -> DeviceId {
+        DeviceId {
+            name: self.name.clone(),
+            index: self.index,
+        }
+    }
+
+    /// Creates device
+    ///
+    /// `shared_type` is whether there are other devices with the same type. Sub-leds are not fetched for such devices, see [Device::sub_leds]
+    #[tracing::instrument(level = "debug", skip(backend))]
+    pub(crate) fn new(
+        backend: SharedBackend,
+        name: String,
+        index: DeviceIndex,
+        led_count: u32,
+        shared_type: bool,
+        resolution: LedResolution,
+    ) -> Result<Self> {
+        let led_count = Self::addressable_led_count(index, led_count);
+        let leds = Self::resolve_leds(&backend, &name, led_count, resolution)?;
+
+        let (friendly_name, sub_leds) = match resolution {
+            LedResolution::Eager => {
+                // device is still usable without its metadata, so the failed fetch doesn't fail the initialization
+                let friendly_name = Self::fetch_friendly_name(&backend, &name, index)
+                    .unwrap_or_else(|error| {
+                        tracing::warn!(name, index, %error, "Cannot fetch device friendly name");
+
+                        name.clone()
+                    });
+                let sub_leds =
+                    Self::fetch_sub_leds(&backend, &name, shared_type).unwrap_or_else(|error| {
+                        tracing::warn!(name, index, %error, "Cannot fetch device sub-leds");
+
+                        Vec::new()
+                    });
+
+                tracing::debug!(friendly_name, ?sub_leds);
+
+                (OnceLock::from(friendly_name), OnceLock::from(sub_leds))
+            }
+            LedResolution::Lazy => (OnceLock::new(), OnceLock::new()),
+        };
 
         Ok(Self {
-            library,
+            kind: DeviceKind::from_device_name(&name),
+            leds,
+            backend,
             name,
+            index,
+            friendly_name,
+            sub_leds,
             led_count,
-            leds,
+            shared_type,
+            resolution,
         })
     }
 
-    /// returns iterator over device's leds
+    fn fetch_friendly_name(
+        backend: &SharedBackend,
+        name: &str,
+        index: DeviceIndex,
+    ) -> Result<String> {
+        let mut backend = backend.lock()?;
+        let capabilities = backend.capabilities();
+
+        let friendly_name = if capabilities.supports(Capability::GetDeviceNameEx) {
+            backend.get_device_name_ex(name, index)?
+        } else if capabilities.supports(Capability::GetDeviceName) {
+            backend
+                .get_device_name(name)?
+                .into_iter()
+                .nth(index as usize)
+                .unwrap_or_else(|| name.to_owned())
+        } else {
+            name.to_owned()
+        };
+
+        Ok(friendly_name)
+    }
+
+    fn fetch_sub_leds(
+        backend: &SharedBackend,
+        name: &str,
+        shared_type: bool,
+    ) -> Result<Vec<String>> {
+        let mut backend = backend.lock()?;
+
+        if shared_type || !backend.capabilities().supports(Capability::GetLedName) {
+            return Ok(Vec::new());
+        }
+
+        backend.get_led_name(name)
+    }
+
+    /// returns number of the device leds that can be addressed through the sdk
+    ///
+    /// Sdk addresses leds by the device type only and it's not documented how the leds of the multiple devices
+    /// with the same type are addressed. Only leds of the first device of the type are exposed, so writes never go to the wrong device
+    fn addressable_led_count(index: DeviceIndex, led_count: u32) -> u32 {
+        if index == 0 {
+            led_count
+        } else {
+            0
+        }
+    }
+
+    /// Creates device from the cached info without calling the sdk
+    #[cfg(feature = "cache")]
+    pub(crate) fn from_inventory(backend: SharedBackend, device: &InventoryDevice) -> Self {
+        let led_count = Self::addressable_led_count(device.index, device.leds.len() as u32);
+        let leds: Vec<_> = device
+            .leds
+            .iter()
+            .zip(0..led_count)
+            .map(|(led, index)| {
+                DeviceLed::from_inventory(backend.clone(), &device.name, index, led)
+            })
+            .collect();
+
+        Self {
+            backend,
+            name: device.name.clone(),
+            kind: DeviceKind::from_device_name(&device.name),
+            friendly_name: OnceLock::from(device.friendly_name.clone()),
+            index: device.index,
+            sub_leds: OnceLock::from(device.sub_leds.clone()),
+            led_count,
+            // sub-leds are taken from the inventory, so they are never fetched and the flag doesn't matter
+            shared_type: false,
+            leds,
+            resolution: LedResolution::Eager,
+        }
+    }
+
+    /// returns iterator over device's leds ordered by their index
     pub fn leds_iter(&self) -> impl Iterator<Item = &DeviceLed> {
-        self.leds.values()
+        self.leds.iter()
+    }
+
+    /// returns device's leds ordered by their index.
+    ///
+    /// Leds are empty for all of the devices with the same type except the first one as the sdk addressing of their leds is unknown
+    pub fn leds(&self) -> &[DeviceLed] {
+        &self.leds
+    }
+
+    /// returns led by its index inside the device
+    pub fn led(&self, index: LedIndex) -> Option<&DeviceLed> {
+        self.leds.get(index as usize)
+    }
+
+    /// returns led by its name or error if the name of some lazy led cannot be fetched
+    pub fn led_by_name(&self, name: &str) -> Result<Option<&DeviceLed>> {
+        for led in &self.leds {
+            if led.try_name()? == name {
+                return Ok(Some(led));
+            }
+        }
+
+        Ok(None)
     }
 
     /// reload cached leds info
     #[tracing::instrument(level = "debug", skip_all, fields(self.name = self.name))]
     pub fn reload(&mut self) -> Result<()> {
-        self.leds = Self::resolve_leds(&self.library, &self.name, self.led_count)?;
+        self.leds = Self::resolve_leds(&self.backend, &self.name, self.led_count, self.resolution)?;
 
         Ok(())
     }
 
     fn resolve_leds(
-        library: &Arc<Mutex<Library>>,
+        backend: &SharedBackend,
         name: &str,
         led_count: u32,
-    ) -> Result<HashMap<String, DeviceLed>> {
-        let leds = (0..led_count)
-            .into_iter()
-            .map(|led_index| {
-                let led = DeviceLed::new(Arc::clone(library), name, led_index)?;
+        resolution: LedResolution,
+    ) -> Result<Vec<DeviceLed>> {
+        (0..led_count)
+            .map(|index| DeviceLed::new(backend.clone(), name, index, resolution))
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use crate::sdk::test_support::{simulated_sdk, simulated_sdk_without};
+    use crate::{Capability, CommonError, DeviceId, DeviceKind, MysticLightSDK, SimulatedBackend};
 
-                Ok((led.name().to_owned(), led))
+    #[test]
+    fn resolves_friendly_names() {
+        let sdk = simulated_sdk();
+
+        let device = sdk
+            .devices_iter()
+            .find(|device| device.name() == "MSI_MB")
+            .unwrap();
+
+        assert_eq!(device.friendly_name(), "MSI MPG Z690 CARBON WIFI");
+        assert_eq!(device.index(), 0);
+        assert_eq!(
+            sdk.device_names("MSI_KEYBOARD").unwrap(),
+            vec!["MSI_KEYBOARD"]
+        );
+    }
+
+    #[test]
+    fn distinguishes_devices_of_the_same_type() {
+        let vga = r#"{
+            "name": "MSI_VGA",
+            "leds": [{
+                "name": "VGA",
+                "supported_styles": ["NoAnimation"],
+                "max_bright": 10,
+                "max_speed": 3,
+                "state": { "style": "NoAnimation", "color": { "red": 0, "green": 0, "blue": 0 }, "bright": 10, "speed": 1 },
+                "sub_leds": [{ "name": "Fan", "color": { "red": 0, "green": 0, "blue": 0 } }]
+            }]
+        }"#;
+        let backend =
+            SimulatedBackend::from_json(&format!(r#"{{ "devices": [{vga}, {vga}] }}"#)).unwrap();
+        let sdk = MysticLightSDK::with_backend(backend).unwrap();
+
+        assert_eq!(sdk.devices_iter().count(), 2);
+
+        let first = sdk
+            .device(&DeviceId {
+                name: String::from("MSI_VGA"),
+                index: 0,
             })
-            .collect::<Result<_>>()?;
+            .unwrap();
+        let second = sdk
+            .device(&DeviceId {
+                name: String::from("MSI_VGA"),
+                index: 1,
+            })
+            .unwrap();
+
+        assert_eq!(first.led(0).unwrap().name(), "VGA");
+        // sdk addressing of the leds of the second device is unknown, so they are not exposed
+        assert!(second.leds().is_empty());
+        // sdk lists sub-leds of both devices together, so they are not attributed to any of them
+        assert!(first.sub_leds().is_empty() && second.sub_leds().is_empty());
+    }
+
+    #[test]
+    fn resolves_sub_led_names() {
+        let sdk = simulated_sdk();
+
+        let keyboard = sdk
+            .devices_iter()
+            .find(|device| device.name() == "MSI_KEYBOARD")
+            .unwrap();
+
+        assert_eq!(keyboard.sub_leds(), ["Esc", "W", "A", "S", "D"]);
+    }
+
+    #[test]
+    fn degrades_without_optional_functions() {
+        let sdk = simulated_sdk_without(vec![Capability::GetDeviceNameEx]);
+        let device = sdk
+            .devices_iter()
+            .find(|device| device.name() == "MSI_MB")
+            .unwrap();
+
+        assert_eq!(device.friendly_name(), "MSI MPG Z690 CARBON WIFI");
+
+        let sdk = simulated_sdk_without(vec![
+            Capability::GetDeviceName,
+            Capability::GetDeviceNameEx,
+            Capability::GetLedName,
+        ]);
+        let keyboard = sdk
+            .devices_iter()
+            .find(|device| device.name() == "MSI_KEYBOARD")
+            .unwrap();
+
+        assert_eq!(keyboard.friendly_name(), "MSI_KEYBOARD");
+        assert!(keyboard.sub_leds().is_empty());
+        assert!(matches!(
+            sdk.device_names("MSI_MB"),
+            Err(CommonError::NotSupportedCapability {
+                capability: Capability::GetDeviceName
+            })
+        ));
+    }
+
+    #[test]
+    fn looks_up_devices_and_leds() {
+        let sdk = simulated_sdk();
+
+        let device = sdk.device(&DeviceId::new("MSI_MB", 0)).unwrap();
+
+        assert_eq!(sdk.devices()[0].id(), device.id());
+        assert_eq!(device.kind(), &DeviceKind::Motherboard);
+        assert!(sdk.device(&DeviceId::new("MSI_MB", 1)).is_none());
+
+        let names: Vec<_> = device.leds().iter().map(|led| led.name()).collect();
 
-        Ok(leds)
+        assert_eq!(names, vec!["JRAINBOW1", "JRGB1"]);
+        assert_eq!(device.led(1).unwrap().name(), "JRGB1");
+        assert_eq!(device.led_by_name("JRGB1").unwrap().unwrap().index(), 1);
+        assert!(device.led_by_name("JRGB2").unwrap().is_none());
     }
 }
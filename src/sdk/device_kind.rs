@@ -0,0 +1,159 @@
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::str::FromStr;
+
+macro_rules! device_kinds {
+    ($($variant:ident => [$($name:literal),+]),* $(,)?) => {
+        /// Category of the device parsed from the sdk device type name e.g. `MSI_MB` is [DeviceKind::Motherboard]
+        ///
+        /// Device types that are unknown to this crate are kept as is in the [DeviceKind::Unknown]
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(from = "String", into = "String")
+        )]
+        #[non_exhaustive]
+        pub enum DeviceKind {
+            $($variant,)*
+            /// device type name that is not known to this crate
+            Unknown(String),
+        }
+
+        impl DeviceKind {
+            /// all of the known device kinds
+            pub const KNOWN: &'static [DeviceKind] = &[$(DeviceKind::$variant),*];
+
+            /// returns name of the kind e.g. `Motherboard`. For the unknown kind returns the sdk device type name
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(DeviceKind::$variant => stringify!($variant),)*
+                    DeviceKind::Unknown(name) => name,
+                }
+            }
+
+            /// returns kind of the device with the passed sdk device type name
+            pub fn from_device_name(name: &str) -> Self {
+                match name {
+                    $($($name)|+ => DeviceKind::$variant,)*
+                    name => DeviceKind::Unknown(name.to_owned()),
+                }
+            }
+        }
+
+        /// Accepts both kind names e.g. `Gpu` and sdk device type names e.g. `MSI_VGA`
+        impl From<&str> for DeviceKind {
+            fn from(name: &str) -> Self {
+                match name {
+                    $(stringify!($variant) => DeviceKind::$variant,)*
+                    name => DeviceKind::from_device_name(name),
+                }
+            }
+        }
+    };
+}
+
+device_kinds! {
+    Motherboard => ["MSI_MB"],
+    Dram => ["MSI_DRAM"],
+    Gpu => ["MSI_VGA"],
+    Cooler => ["MSI_COOLER"],
+    LightStrip => ["MSI_LIGHTSTRIP", "MSI_LEDSTRIP"],
+    Keyboard => ["MSI_KEYBOARD"],
+    Mouse => ["MSI_MOUSE"],
+    Mousepad => ["MSI_MOUSEPAD"],
+    Headset => ["MSI_HEADSET"],
+    HeadsetStand => ["MSI_HEADSET_STAND"],
+    Speaker => ["MSI_SPEAKER"],
+}
+
+impl DeviceKind {
+    /// returns whether the device type is known to this crate
+    pub fn is_known(&self) -> bool {
+        !matches!(self, DeviceKind::Unknown(_))
+    }
+}
+
+impl From<String> for DeviceKind {
+    fn from(name: String) -> Self {
+        match DeviceKind::from(name.as_str()) {
+            DeviceKind::Unknown(_) => DeviceKind::Unknown(name),
+            kind => kind,
+        }
+    }
+}
+
+impl From<DeviceKind> for String {
+    fn from(kind: DeviceKind) -> Self {
+        match kind {
+            DeviceKind::Unknown(name) => name,
+            kind => kind.as_str().to_owned(),
+        }
+    }
+}
+
+impl FromStr for DeviceKind {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(DeviceKind::from(name))
+    }
+}
+
+impl Display for DeviceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Category of the device e.g. `Motherboard` or `Gpu`. Unknown devices use the sdk device type name e.g. `MSI_CASE`
+#[cfg(feature = "async-graphql")]
+#[async_graphql::Scalar(name = "DeviceKind")]
+impl async_graphql::ScalarType for DeviceKind {
+    fn parse(value: async_graphql::Value) -> async_graphql::InputValueResult<Self> {
+        match value {
+            async_graphql::Value::String(name) => Ok(DeviceKind::from(name)),
+            value => Err(async_graphql::InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> async_graphql::Value {
+        async_graphql::Value::String(self.as_str().to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_device_names() {
+        assert_eq!(
+            DeviceKind::from_device_name("MSI_MB"),
+            DeviceKind::Motherboard
+        );
+        assert_eq!(
+            DeviceKind::from_device_name("MSI_LEDSTRIP"),
+            DeviceKind::LightStrip
+        );
+        assert_eq!(
+            DeviceKind::from_device_name("MSI_CASE"),
+            DeviceKind::Unknown(String::from("MSI_CASE"))
+        );
+        assert!(!DeviceKind::from_device_name("Gpu").is_known());
+    }
+
+    #[test]
+    fn kind_names_roundtrip() {
+        assert_eq!(DeviceKind::from("Gpu"), DeviceKind::Gpu);
+        assert_eq!(DeviceKind::from("MSI_VGA"), DeviceKind::Gpu);
+        assert_eq!(DeviceKind::HeadsetStand.to_string(), "HeadsetStand");
+        assert!(DeviceKind::KNOWN
+            .iter()
+            .all(|kind| kind.is_known() && DeviceKind::from(kind.to_string()) == *kind));
+        assert_eq!(
+            String::from(DeviceKind::from("MSI_CASE".to_owned())),
+            "MSI_CASE"
+        );
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::PoisonError;
 use custom_error::custom_error;
 use libloading::Error as LibLoadingError;
 
-use super::types::{BrightLevel, SpeedLevel};
+use super::backend::Capability;
+use super::types::{BrightLevel, MysticLightSdkResult, SpeedLevel};
 
 custom_error! {
   /// Errors generated by the MysticLight SDK, see [SDK docs](https://www.msi.com/Landing/mystic-light-rgb-gaming-pc/download)
@@ -18,11 +19,30 @@ custom_error! {
     InvalidArgument = "The parameter value is not valid",
     DeviceNotFound = "The device not found",
     NotSupported = "Requested feature is not supported in the selected LED",
-    Unknown = "Unknown error",
+    /// Result code that is not documented by the sdk
+    Unknown{code: MysticLightSdkResult} = "Unknown error with code {code}",
+}
+
+impl MysticLightSDKError {
+    /// Returns raw result code of the underlying sdk call
+    pub fn code(&self) -> MysticLightSdkResult {
+        match self {
+            Self::Generic => -1,
+            Self::Timeout => -2,
+            Self::NotImplemented => -3,
+            Self::NotInitialized => -4,
+            Self::InvalidArgument => -101,
+            Self::DeviceNotFound => -102,
+            Self::NotSupported => -103,
+            Self::Unknown { code } => *code,
+        }
+    }
 }
 
 custom_error! {
   /// Errors for bad sdk usage
+  #[derive(Clone, PartialEq, Eq)]
+  #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
   #[non_exhaustive]
   pub UsageError
       /// Tried to set style that is not supported by current device
@@ -36,12 +56,28 @@ custom_error! {
       ExcessSpeedLevel{level: SpeedLevel, max_level: SpeedLevel} = "Passed speed level={level} exceeds supported {max_level}",
 }
 
+custom_error! {
+  /// Errors while converting values returned by the sdk dll to the Rust types
+  #[derive(Clone, PartialEq, Eq)]
+  #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+  #[non_exhaustive]
+  pub ConversionError
+    NullPointer = "Got null pointer",
+    Bstr{message: String} = "Cannot convert BSTR: {message}",
+    SafeArray{message: String} = "Cannot convert SAFEARRAY: {message}",
+    /// Sdk returned number as the string that cannot be parsed
+    InvalidNumber{value: String} = "Cannot parse number from '{value}'",
+}
+
 custom_error! {
   /// Errors with multithreading
-  #[derive(Clone)]
+  #[derive(Clone, PartialEq, Eq)]
+  #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
   #[non_exhaustive]
   pub SyncError
-    Poison = "Shared object (Mutex or RwLock) is poisoned"
+    Poison = "Shared object (Mutex or RwLock) is poisoned",
+    /// Worker thread that executes sdk calls is stopped or the call panicked
+    Disconnected = "Sdk worker thread is stopped",
 }
 
 impl<T> From<PoisonError<T>> for SyncError {
@@ -54,12 +90,37 @@ custom_error! {
   /// CommonError that may happen during usage of this library
   #[non_exhaustive]
   pub CommonError
-      SdkError{source: MysticLightSDKError} = "SdkError({source})",
+      /// Error returned by the sdk call with the description provided by the sdk itself if any
+      SdkError{source: MysticLightSDKError, description: Option<String>} = @{
+          match description {
+              Some(description) => format!("SdkError({source}: {description})"),
+              None => format!("SdkError({source})"),
+          }
+      },
       LibraryError{source: LibLoadingError} = "LibraryError({source})",
+      /// Loaded dll doesn't export some of the required sdk functions
+      MissingExports{exports: Vec<String>} = @{ format!("MissingExports({})", exports.join(", ")) },
+      /// Backend doesn't support the optional sdk function
+      NotSupportedCapability{capability: Capability} = "NotSupportedCapability({capability})",
+      /// Replayed call is missing in the recorded trace, see [ReplayBackend](crate::ReplayBackend)
+      NotRecorded{call: String} = "NotRecorded({call})",
+      /// Connection or protocol failure of the network api
+      RemoteError{message: String} = "RemoteError({message})",
+      /// Malformed response of the sdk dll
+      ConversionError{source: ConversionError} = "ConversionError({source})",
       UsageError{source: UsageError} = "UsageError({source})",
       SyncError{source: SyncError} = "SyncError({source})",
 }
 
+impl From<MysticLightSDKError> for CommonError {
+    fn from(source: MysticLightSDKError) -> Self {
+        Self::SdkError {
+            source,
+            description: None,
+        }
+    }
+}
+
 impl<T> From<PoisonError<T>> for CommonError {
     fn from(error: PoisonError<T>) -> Self {
         Self::SyncError {
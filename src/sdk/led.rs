@@ -1,20 +1,17 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::fmt::Debug;
-use std::ptr::null_mut;
-use std::sync::{Arc, Mutex};
-
-use libloading::{Library, Symbol};
-
-use crate::winapi::{Bstr, FromSafeArray};
-use crate::MysticLightSDK;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
 
+use super::backend::{Capability, LedInfo, SharedBackend};
+#[cfg(feature = "cache")]
+use super::cache::InventoryLed;
 use super::color::Color;
 use super::error::UsageError;
-use super::types::{
-    BrightLevel, ColorLevel, DeviceName, LedIndex, LedName, LedStyle, MysticLightSdkResult, Result,
-    SpeedLevel,
-};
-use super::{CommonError, LedStyles, MysticLightSDKError};
+use super::style::{LedStyle, StyleCapabilities};
+use super::sub_led::{SubLed, SubLedWriteMode};
+use super::task::Reply;
+use super::types::{BrightLevel, LedIndex, Result, SpeedLevel};
+use super::{CommonError, MysticLightSDKError};
 
 /// Represents state of the single led
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,20 +19,21 @@ use super::{CommonError, LedStyles, MysticLightSDKError};
 #[cfg_attr(feature = "async-graphql", derive(async_graphql::SimpleObject))]
 pub struct DeviceLedState {
     /// current style of the led
-    pub style: String,
-    /// current color of the led (some of the styles do not support this, so there will be fake data in this case)
+    pub style: LedStyle,
+    /// current color of the led (some of the styles do not support this, so there will be fake data in this case, see [DeviceLed::style_capabilities])
     pub color: Color,
-    /// current brightness of the led (some of the styles do not support this, so there will be fake data in this case)
+    /// current brightness of the led (some of the styles do not support this, so there will be fake data in this case, see [DeviceLed::style_capabilities])
     pub bright: u32,
-    /// current speed of the led (some of the styles do not support this, so there will be fake data in this case)
+    /// current speed of the led (some of the styles do not support this, so there will be fake data in this case, see [DeviceLed::style_capabilities])
     pub speed: u32,
 }
 
 /// Represents state of the single led, but with optional fields
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "async-graphql", derive(async_graphql::InputObject))]
 pub struct DeviceLedStateInput {
     /// current style of the led
-    pub style: Option<String>,
+    pub style: Option<LedStyle>,
     /// current color of the led (some of the styles do not support this, so there will be fake data in this case)
     pub color: Option<Color>,
     /// current brightness of the led (some of the styles do not support this, so there will be fake data in this case)
@@ -44,24 +42,60 @@ pub struct DeviceLedStateInput {
     pub speed: Option<u32>,
 }
 
+/// Defines when metadata of the leds (name, supported styles, max levels) is fetched from the sdk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LedResolution {
+    /// metadata of every led is fetched when the device is created
+    #[default]
+    Eager,
+    /// metadata of the led is fetched on the first access and memoised. Calls that don't need metadata e.g. [DeviceLed::set_color] never fetch it.
+    /// Friendly name and sub-leds of the device are fetched on the first access as well
+    Lazy,
+}
+
+/// Static info of the led fetched from the sdk
+#[derive(Debug, Clone)]
+struct LedMetadata {
+    name: String,
+    supported_styles: Vec<LedStyle>,
+    max_bright: BrightLevel,
+    max_speed: SpeedLevel,
+}
+
 /// Represents single led of the device
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
 pub struct DeviceLed {
-    // public fields
-    name: String,
-    supported_styles: HashSet<String>,
-    max_bright: u32,
-    max_speed: u32,
+    /// shared between clones, so metadata fetched through any of them is memoised for all
+    metadata: Arc<OnceLock<LedMetadata>>,
+    /// last state read from or written to the led, shared between clones. Writes skip parameters that equal to it
+    known_state: Arc<Mutex<DeviceLedStateInput>>,
+    /// capabilities found by [DeviceLed::probe], shared between clones. They take precedence over [LedStyle::capabilities]
+    probed: Arc<Mutex<HashMap<LedStyle, StyleCapabilities>>>,
 
     // internal field that required to make api calls
-    #[cfg_attr(feature = "serde", serde(skip))]
-    library: Arc<Mutex<Library>>,
-
-    #[cfg_attr(feature = "serde", serde(skip))]
-    device_name: Bstr,
+    backend: SharedBackend,
+    device_name: String,
+    led_index: LedIndex,
+}
 
-    #[cfg_attr(feature = "serde", serde(skip))]
-    led_index: u32,
+#[cfg(feature = "serde")]
+impl serde::Serialize for DeviceLed {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeStruct};
+
+        let metadata = self.metadata().map_err(S::Error::custom)?;
+        let mut led = serializer.serialize_struct("DeviceLed", 5)?;
+
+        led.serialize_field("name", &metadata.name)?;
+        led.serialize_field("index", &self.led_index)?;
+        led.serialize_field("supported_styles", &metadata.supported_styles)?;
+        led.serialize_field("max_bright", &metadata.max_bright)?;
+        led.serialize_field("max_speed", &metadata.max_speed)?;
+        led.end()
+    }
 }
 
 /// Represents single led of the device
@@ -69,28 +103,41 @@ pub struct DeviceLed {
 #[async_graphql::Object]
 impl DeviceLed {
     #[graphql(name = "name")]
-    async fn async_graphql_name(&self) -> &str {
-        self.name()
+    async fn async_graphql_name(&self) -> Result<&str> {
+        self.try_name()
+    }
+
+    #[graphql(name = "index")]
+    async fn async_graphql_index(&self) -> LedIndex {
+        self.index()
     }
 
     #[graphql(name = "supportedStyles")]
-    async fn async_graphql_supported_styles(&self) -> &HashSet<String> {
-        self.supported_styles()
+    async fn async_graphql_supported_styles(&self) -> Result<&[LedStyle]> {
+        self.try_supported_styles()
+    }
+
+    /// returns parameters honoured by every supported style
+    #[graphql(name = "styleCapabilities")]
+    async fn async_graphql_style_capabilities(&self) -> Result<Vec<StyleCapabilities>> {
+        self.resolve()?;
+
+        Ok(self.supported_style_capabilities())
     }
 
     #[graphql(name = "maxBright")]
-    async fn async_graphql_max_bright(&self) -> u32 {
-        self.max_bright()
+    async fn async_graphql_max_bright(&self) -> Result<u32> {
+        self.try_max_bright()
     }
 
     #[graphql(name = "maxSpeed")]
-    async fn async_graphql_max_speed(&self) -> u32 {
-        self.max_speed()
+    async fn async_graphql_max_speed(&self) -> Result<u32> {
+        self.try_max_speed()
     }
 
     #[graphql(name = "state")]
     async fn async_graphql_get_state(&self) -> Result<DeviceLedState> {
-        self.get_state()
+        self.get_state_async().await
     }
 }
 
@@ -105,7 +152,7 @@ pub struct DeviceLedMutation<'a>(pub &'a DeviceLed);
 impl<'a> DeviceLedMutation<'a> {
     /// updates state for the device led
     pub async fn set_state(&self, state: DeviceLedStateInput) -> Result<bool> {
-        self.0.merge_with_state(&state)?;
+        self.0.merge_with_state_async(&state).await?;
 
         Ok(true)
     }
@@ -114,107 +161,204 @@ impl<'a> DeviceLedMutation<'a> {
 impl Debug for DeviceLed {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DeviceLed")
-            .field("name", &self.name)
-            .field("supported_styles", &self.supported_styles)
-            .field("max_bright", &self.max_bright)
-            .field("max_speed", &self.max_speed)
+            .field("index", &self.led_index)
+            .field("device_name", &self.device_name)
+            .field("metadata", &self.metadata.get())
             .finish()
     }
 }
 
 impl DeviceLed {
+    /// returns name of the led or empty string if the metadata of the lazy led cannot be fetched.
+    /// The error is logged and the metadata is fetched again on the next access, use [DeviceLed::try_name] to handle it
     pub fn name(&self) -> &str {
-        &self.name
+        self.metadata_or_warn()
+            .map(|metadata| metadata.name.as_str())
+            .unwrap_or_default()
+    }
+
+    /// returns name of the led or error if the metadata of the lazy led cannot be fetched, see [DeviceLed::resolve]
+    pub fn try_name(&self) -> Result<&str> {
+        self.metadata().map(|metadata| metadata.name.as_str())
+    }
+
+    /// returns index of the led inside the device
+    pub fn index(&self) -> LedIndex {
+        self.led_index
+    }
+
+    /// returns styles supported by the led or empty list if the metadata of the lazy led cannot be fetched, see [DeviceLed::name]
+    pub fn supported_styles(&self) -> &[LedStyle] {
+        self.metadata_or_warn()
+            .map(|metadata| metadata.supported_styles.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// returns styles supported by the led or error if the metadata of the lazy led cannot be fetched, see [DeviceLed::resolve]
+    pub fn try_supported_styles(&self) -> Result<&[LedStyle]> {
+        self.metadata()
+            .map(|metadata| metadata.supported_styles.as_slice())
     }
 
-    pub fn supported_styles(&self) -> &HashSet<String> {
-        &self.supported_styles
+    /// returns whether the led supports the passed style
+    pub fn supports_style(&self, style: &LedStyle) -> bool {
+        self.supported_styles()
+            .iter()
+            .any(|supported| supported == style)
     }
 
+    /// returns parameters of the led state honoured by the passed style on this led or `None` if the style is not supported.
+    ///
+    /// Results of the [DeviceLed::probe] are returned if the style was probed, otherwise they come from the [LedStyle::capabilities] table
+    /// and styles unknown to the table are reported as honouring every parameter. Brightness and speed are not honoured if the led doesn't support any levels of them.
+    /// Capabilities are advisory only: writes are never filtered by them
+    pub fn style_capabilities(&self, style: &LedStyle) -> Option<StyleCapabilities> {
+        if !self.supports_style(style) {
+            return None;
+        }
+
+        if let Some(capabilities) = self.probed_capabilities(style) {
+            return Some(capabilities);
+        }
+
+        let capabilities = style.capabilities().unwrap_or(StyleCapabilities {
+            style: style.clone(),
+            color: true,
+            bright: true,
+            speed: true,
+        });
+
+        Some(StyleCapabilities {
+            bright: capabilities.bright && self.max_bright() > 0,
+            speed: capabilities.speed && self.max_speed() > 0,
+            ..capabilities
+        })
+    }
+
+    /// returns capabilities of the style found by the [DeviceLed::probe]
+    pub(crate) fn probed_capabilities(&self, style: &LedStyle) -> Option<StyleCapabilities> {
+        self.probed_mut().get(style).cloned()
+    }
+
+    pub(crate) fn probed_mut(&self) -> MutexGuard<'_, HashMap<LedStyle, StyleCapabilities>> {
+        self.probed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Run blocking call on the worker of the led backend, so it doesn't wait for the async calls to the other backends
+    pub(crate) fn spawn_blocking<T, F>(&self, f: F) -> Reply<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        self.backend.spawn_blocking(f)
+    }
+
+    /// returns capabilities of every supported style in the order of [DeviceLed::supported_styles]
+    pub fn supported_style_capabilities(&self) -> Vec<StyleCapabilities> {
+        self.supported_styles()
+            .iter()
+            .filter_map(|style| self.style_capabilities(style))
+            .collect()
+    }
+
+    /// returns max brightness level of the led or `0` if the metadata of the lazy led cannot be fetched, see [DeviceLed::name]
     pub fn max_bright(&self) -> u32 {
-        self.max_bright
+        self.metadata_or_warn()
+            .map(|metadata| metadata.max_bright)
+            .unwrap_or_default()
+    }
+
+    /// returns max brightness level of the led or error if the metadata of the lazy led cannot be fetched, see [DeviceLed::resolve]
+    pub fn try_max_bright(&self) -> Result<u32> {
+        self.metadata().map(|metadata| metadata.max_bright)
     }
 
+    /// returns max speed level of the led or `0` if the metadata of the lazy led cannot be fetched, see [DeviceLed::name]
     pub fn max_speed(&self) -> u32 {
-        self.max_speed
+        self.metadata_or_warn()
+            .map(|metadata| metadata.max_speed)
+            .unwrap_or_default()
+    }
+
+    /// returns max speed level of the led or error if the metadata of the lazy led cannot be fetched, see [DeviceLed::resolve]
+    pub fn try_max_speed(&self) -> Result<u32> {
+        self.metadata().map(|metadata| metadata.max_speed)
+    }
+
+    /// Fetch metadata of the led if it is not fetched yet. Allows to handle errors of the lazy resolution, see [LedResolution::Lazy]
+    pub fn resolve(&self) -> Result<()> {
+        self.metadata().map(|_| ())
+    }
+
+    /// returns whether the metadata of the led is already fetched
+    pub fn is_resolved(&self) -> bool {
+        self.metadata.get().is_some()
+    }
+
+    fn metadata(&self) -> Result<&LedMetadata> {
+        if let Some(metadata) = self.metadata.get() {
+            return Ok(metadata);
+        }
+
+        let metadata = Self::fetch_metadata(&self.backend, &self.device_name, self.led_index)?;
+
+        Ok(self.metadata.get_or_init(|| metadata))
+    }
+
+    fn metadata_or_warn(&self) -> Option<&LedMetadata> {
+        self.metadata()
+            .map_err(|error| {
+                tracing::warn!(device_name = self.device_name, led_index = self.led_index, %error, "Cannot fetch led metadata");
+            })
+            .ok()
     }
 
-    #[tracing::instrument(level = "debug", skip(library))]
+    /// Creates led
+    #[tracing::instrument(level = "debug", skip(backend))]
     pub(crate) fn new(
-        library: Arc<Mutex<Library>>,
+        backend: SharedBackend,
         device_name: &str,
-        led_index: u32,
+        led_index: LedIndex,
+        resolution: LedResolution,
     ) -> Result<Self> {
-        let get_led_info: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                led_name: *mut LedName,
-                led_styles: *mut LedStyles,
-            ) -> MysticLightSdkResult,
-        >;
-        let get_led_max_bright: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                max_bright: *mut BrightLevel,
-            ) -> MysticLightSdkResult,
-        >;
-        let get_led_max_speed: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                max_speed: *mut SpeedLevel,
-            ) -> MysticLightSdkResult,
-        >;
-
-        let library_instance = library.lock()?;
-
-        unsafe {
-            get_led_info = library_instance.get(b"MLAPI_GetLedInfo")?;
-            get_led_max_bright = library_instance.get(b"MLAPI_GetLedMaxBright")?;
-            get_led_max_speed = library_instance.get(b"MLAPI_GetLedMaxSpeed")?;
-        }
+        let led = Self {
+            backend,
+            device_name: device_name.to_owned(),
+            led_index,
+            metadata: Arc::default(),
+            known_state: Arc::default(),
+            probed: Arc::default(),
+        };
 
-        let device_name = Bstr::from(device_name);
-        let mut led_name: LedName = null_mut();
-        let mut led_styles: LedStyles = null_mut();
-        let mut max_bright = 0u32;
-        let mut max_speed = 0u32;
-
-        unsafe {
-            MysticLightSDK::parse_result(get_led_info(
-                device_name.as_ptr(),
-                led_index,
-                &mut led_name,
-                &mut led_styles,
-            ))?;
-
-            MysticLightSDK::parse_result(get_led_max_bright(
-                device_name.as_ptr(),
-                led_index,
-                &mut max_bright,
-            ))?;
-
-            MysticLightSDK::parse_result(get_led_max_speed(
-                device_name.as_ptr(),
-                led_index,
-                &mut max_speed,
-            ))?;
+        if resolution == LedResolution::Eager {
+            led.resolve()?;
         }
 
-        let supported_styles = HashSet::from_safearray(led_styles);
+        Ok(led)
+    }
 
-        let name = Bstr::from(led_name).to_string();
+    fn fetch_metadata(
+        backend: &SharedBackend,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<LedMetadata> {
+        let mut backend_instance = backend.lock()?;
+
+        let LedInfo {
+            name,
+            supported_styles,
+        } = backend_instance.get_led_info(device_name, led_index)?;
+        let max_bright = backend_instance.get_led_max_bright(device_name, led_index)?;
+        let max_speed = backend_instance.get_led_max_speed(device_name, led_index)?;
 
-        drop(library_instance);
+        drop(backend_instance);
+
+        let supported_styles: Vec<LedStyle> =
+            supported_styles.into_iter().map(LedStyle::from).collect();
 
         tracing::debug!(name, ?supported_styles, max_bright, max_speed);
 
-        Ok(Self {
-            library,
-            device_name,
-            led_index,
+        Ok(LedMetadata {
             name,
             supported_styles,
             max_bright,
@@ -222,90 +366,54 @@ impl DeviceLed {
         })
     }
 
-    /// Return state of the led
-    #[tracing::instrument(level = "debug", skip(self), fields(self.name = self.name))]
-    pub fn get_state(&self) -> Result<DeviceLedState> {
-        let get_led_style: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                style: *mut LedStyle,
-            ) -> MysticLightSdkResult,
-        >;
-        let get_led_color: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                red: *mut ColorLevel,
-                green: *mut ColorLevel,
-                blue: *mut ColorLevel,
-            ) -> MysticLightSdkResult,
-        >;
-        let get_led_bright: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                bright: *mut BrightLevel,
-            ) -> MysticLightSdkResult,
-        >;
-        let get_led_speed: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                speed: *mut SpeedLevel,
-            ) -> MysticLightSdkResult,
-        >;
-
-        let library = self.library.lock()?;
-
-        unsafe {
-            get_led_style = library.get(b"MLAPI_GetLedStyle")?;
-            get_led_color = library.get(b"MLAPI_GetLedColor")?;
-            get_led_speed = library.get(b"MLAPI_GetLedSpeed")?;
-            get_led_bright = library.get(b"MLAPI_GetLedBright")?;
-        }
-
-        let mut style: LedStyle = null_mut();
-        let mut red = 0u32;
-        let mut green = 0u32;
-        let mut blue = 0u32;
-        let mut speed = 0u32;
-        let mut bright = 0u32;
-
-        unsafe {
-            MysticLightSDK::parse_result(get_led_style(
-                self.device_name.as_ptr(),
-                self.led_index,
-                &mut style,
-            ))?;
+    /// Creates led from the cached info without calling the sdk
+    #[cfg(feature = "cache")]
+    pub(crate) fn from_inventory(
+        backend: SharedBackend,
+        device_name: &str,
+        led_index: LedIndex,
+        led: &InventoryLed,
+    ) -> Self {
+        let metadata = LedMetadata {
+            name: led.name.clone(),
+            supported_styles: led.supported_styles.clone(),
+            max_bright: led.max_bright,
+            max_speed: led.max_speed,
+        };
 
-            MysticLightSDK::parse_result(get_led_color(
-                self.device_name.as_ptr(),
-                self.led_index,
-                &mut red,
-                &mut green,
-                &mut blue,
-            ))?;
+        Self {
+            backend,
+            device_name: device_name.to_owned(),
+            led_index,
+            metadata: Arc::new(OnceLock::from(metadata)),
+            known_state: Arc::default(),
+            probed: Arc::default(),
+        }
+    }
 
-            MysticLightSDK::parse_result(get_led_speed(
-                self.device_name.as_ptr(),
-                self.led_index,
-                &mut speed,
-            ))?;
+    /// Return state of the led
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
+    pub fn get_state(&self) -> Result<DeviceLedState> {
+        let mut backend = self.backend.lock()?;
 
-            MysticLightSDK::parse_result(get_led_bright(
-                self.device_name.as_ptr(),
-                self.led_index,
-                &mut bright,
-            ))?;
-        }
+        let style = LedStyle::from(backend.get_led_style(&self.device_name, self.led_index)?);
+        let color = backend.get_led_color(&self.device_name, self.led_index)?;
+        let speed = backend.get_led_speed(&self.device_name, self.led_index)?;
+        let bright = backend.get_led_bright(&self.device_name, self.led_index)?;
 
-        let color = Color { red, green, blue };
+        drop(backend);
 
         tracing::debug!(?color, bright, speed);
 
+        *self.known_state_mut() = DeviceLedStateInput {
+            style: Some(style.clone()),
+            color: Some(color.clone()),
+            bright: Some(bright),
+            speed: Some(speed),
+        };
+
         Ok(DeviceLedState {
-            style: Bstr::from(style).to_string(),
+            style,
             color,
             bright,
             speed,
@@ -313,42 +421,66 @@ impl DeviceLed {
     }
 
     /// Set led style
-    #[tracing::instrument(level = "debug", skip(self), fields(self.name = self.name))]
-    pub fn set_style(&self, style: &str) -> Result<()> {
-        let set_led_style: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                style: LedStyle,
-            ) -> MysticLightSdkResult,
-        >;
-
-        if !self.supported_styles.contains(style) {
-            let mut iter = self.supported_styles.iter();
-            let first = iter.next().unwrap().to_string();
-
-            return Err(UsageError::NotSupportedStyle {
-                style: style.to_string(),
-                supported_styles: iter.fold(first, |acc, s| format!("{}, {}", acc, s)),
-            }
-            .into());
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
+    pub fn set_style(&self, style: &LedStyle) -> Result<()> {
+        self.resolve()?;
+
+        if !self.supports_style(style) {
+            return Err(self.not_supported_style(style));
         }
 
-        unsafe {
-            let library = self.library.lock()?;
+        let result =
+            self.backend
+                .lock()?
+                .set_led_style(&self.device_name, self.led_index, style.as_str());
 
-            set_led_style = library.get(b"MLAPI_SetLedStyle")?;
+        let mut known_state = self.known_state_mut();
 
-            let style: Bstr = style.into();
+        // other parameters might be reset by the style change, so they are not known anymore
+        if result.is_err() || known_state.style.as_ref() != Some(style) {
+            *known_state = DeviceLedStateInput {
+                style: result.is_ok().then(|| style.clone()),
+                ..Default::default()
+            };
+        }
 
-            MysticLightSDK::parse_result(set_led_style(
-                self.device_name.as_ptr(),
-                self.led_index,
-                style.as_ptr(),
-            ))?;
+        result
+    }
+
+    /// Forget the last known state of the led, so the next [DeviceLed::set_state] or [DeviceLed::merge_with_state] sends every parameter.
+    /// Use it when the led might be changed outside of this crate e.g. by the MSI Center
+    pub fn forget_state(&self) {
+        *self.known_state_mut() = DeviceLedStateInput::default();
+    }
+
+    fn known_state_mut(&self) -> MutexGuard<'_, DeviceLedStateInput> {
+        // known state is always valid even if the other thread has panicked while holding the lock
+        self.known_state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// returns last known state of the led or the empty one if the write is forced
+    fn known_state(&self, force: bool) -> DeviceLedStateInput {
+        if force {
+            return DeviceLedStateInput::default();
         }
 
-        Ok(())
+        self.known_state_mut().clone()
+    }
+
+    fn not_supported_style(&self, style: &LedStyle) -> CommonError {
+        let supported_styles: Vec<_> = self
+            .supported_styles()
+            .iter()
+            .map(LedStyle::as_str)
+            .collect();
+
+        UsageError::NotSupportedStyle {
+            style: style.to_string(),
+            supported_styles: supported_styles.join(", "),
+        }
+        .into()
     }
 
     /// Set led color
@@ -356,157 +488,292 @@ impl DeviceLed {
     /// # Caveats
     ///
     /// Some of the styles do not support setting color for the led.
-    /// In this case this method will return `Err(CommonError::MysticLightSDKError(Timeout))` as this error is returned by the underlying dll
-    #[tracing::instrument(level = "debug", skip(self), fields(self.name = self.name))]
+    /// In this case this method will return `Err(CommonError::SdkError { source: MysticLightSDKError::Timeout, .. })` as this error is returned by the underlying dll
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
     pub fn set_color(&self, color: &Color) -> Result<()> {
-        let set_led_color: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                r: ColorLevel,
-                g: ColorLevel,
-                b: ColorLevel,
-            ) -> MysticLightSdkResult,
-        >;
+        let result = self
+            .backend
+            .lock()?
+            .set_led_color(&self.device_name, self.led_index, color);
 
-        unsafe {
-            let library = self.library.lock()?;
+        self.known_state_mut().color = result.is_ok().then(|| color.clone());
+
+        result
+    }
 
-            set_led_color = library.get(b"MLAPI_SetLedColor")?;
+    /// returns sub-led of the led area by its name
+    pub fn sub_led(&self, name: &str) -> SubLed<'_> {
+        SubLed::new(self, name.to_owned())
+    }
 
-            let &Color { red, green, blue } = color;
+    /// Set color for the single sub-led of the led area (e.g. key of the keyboard)
+    ///
+    /// `mode` defines whether the call waits until the hardware applies the color, see [SubLedWriteMode].
+    /// Async write falls back to the sync one if the backend doesn't support [Capability::SetLedColorEx]
+    ///
+    /// # Caveats
+    ///
+    /// Same as for the [DeviceLed::set_color]
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
+    pub fn set_sub_led_color(
+        &self,
+        sub_led: &str,
+        color: &Color,
+        mode: SubLedWriteMode,
+        force_update: bool,
+    ) -> Result<()> {
+        let mut backend = self.backend.lock()?;
+
+        let mode = match mode {
+            SubLedWriteMode::Async
+                if !backend.capabilities().supports(Capability::SetLedColorEx) =>
+            {
+                SubLedWriteMode::Sync
+            }
+            mode => mode,
+        };
 
-            MysticLightSDK::parse_result(set_led_color(
-                self.device_name.as_ptr(),
+        let result = match mode {
+            SubLedWriteMode::Async => backend.set_led_color_ex(
+                &self.device_name,
+                self.led_index,
+                sub_led,
+                color,
+                force_update,
+            ),
+            SubLedWriteMode::Sync => backend.set_led_color_sync(
+                &self.device_name,
                 self.led_index,
-                red,
-                green,
-                blue,
-            ))?;
+                sub_led,
+                color,
+                force_update,
+            ),
+        };
+
+        drop(backend);
+
+        // color of the led area is not uniform anymore
+        self.known_state_mut().color = None;
+
+        result
+    }
+
+    /// Set colors for the individual sub-leds of the led area (e.g. keys of the keyboard) with the single sdk call
+    ///
+    /// `colors` maps sub-led names to the colors.
+    /// Sub-leds are written one by one if the backend doesn't support [Capability::SetLedColors].
+    /// If neither [Capability::SetLedColorEx] nor [Capability::SetLedColorSync] is supported, the color is written to the whole led area
+    /// as long as all of the passed colors are the same, otherwise `Err(CommonError::NotSupportedCapability)` is returned
+    ///
+    /// # Caveats
+    ///
+    /// Same as for the [DeviceLed::set_color]
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
+    pub fn set_sub_led_colors(&self, colors: &HashMap<String, Color>) -> Result<()> {
+        if colors.is_empty() {
+            return Ok(());
         }
 
-        Ok(())
+        let capabilities = self.backend.lock()?.capabilities();
+
+        if !capabilities.supports(Capability::SetLedColors) {
+            if capabilities.supports(Capability::SetLedColorEx)
+                || capabilities.supports(Capability::SetLedColorSync)
+            {
+                tracing::debug!("batch write is not supported, writing sub-leds one by one");
+
+                for (name, color) in colors {
+                    self.set_sub_led_color(name, color, SubLedWriteMode::Async, false)?;
+                }
+
+                return Ok(());
+            }
+
+            let mut distinct_colors = colors.values();
+            let color = distinct_colors.next().expect("colors are not empty");
+
+            if distinct_colors.any(|other| other != color) {
+                return Err(CommonError::NotSupportedCapability {
+                    capability: Capability::SetLedColors,
+                });
+            }
+
+            tracing::debug!(
+                "sub-led writes are not supported, writing color of the whole led area"
+            );
+
+            return self.set_color(color);
+        }
+
+        let colors: Vec<_> = colors
+            .iter()
+            .map(|(name, color)| (name.clone(), color.clone()))
+            .collect();
+
+        let result =
+            self.backend
+                .lock()?
+                .set_led_colors(&self.device_name, self.led_index, &colors);
+
+        self.known_state_mut().color = None;
+
+        result
     }
 
     /// Set led brightness
     ///
     /// # Caveats
     ///
-    /// Some of the styles do not support setting color for the led.
-    /// In this case this method will return `Err(CommonError::MysticLightSDKError(Timeout))` as this error is returned by the underlying dll
-    #[tracing::instrument(level = "debug", skip(self), fields(self.name = self.name))]
+    /// Some of the styles ignore the brightness and the sdk may reject the write the same way as for the [DeviceLed::set_color]
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
     pub fn set_bright(&self, bright: BrightLevel) -> Result<()> {
-        if bright > self.max_bright {
+        let max_bright = self.metadata()?.max_bright;
+
+        if bright > max_bright {
             return Err(UsageError::ExcessBrightLevel {
                 level: bright,
-                max_level: self.max_bright,
+                max_level: max_bright,
             }
             .into());
         }
 
-        let set_led_bright: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                level: BrightLevel,
-            ) -> MysticLightSdkResult,
-        >;
+        let result = self
+            .backend
+            .lock()?
+            .set_led_bright(&self.device_name, self.led_index, bright);
 
-        unsafe {
-            let library = self.library.lock()?;
+        self.known_state_mut().bright = result.is_ok().then_some(bright);
 
-            set_led_bright = library.get(b"MLAPI_SetLedBright")?;
-
-            MysticLightSDK::parse_result(set_led_bright(
-                self.device_name.as_ptr(),
-                self.led_index,
-                bright,
-            ))?;
-        }
-
-        Ok(())
+        result
     }
 
     /// Set led speed
     ///
     /// # Caveats
     ///
-    /// Some of the styles do not support setting color for the led.
-    /// In this case this method will return `Err(CommonError::MysticLightSDKError(Timeout))` as this error is returned by the underlying dll
-    #[tracing::instrument(level = "debug", skip(self), fields(self.name = self.name))]
+    /// Some of the styles ignore the speed and the sdk may reject the write the same way as for the [DeviceLed::set_color]
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
     pub fn set_speed(&self, speed: SpeedLevel) -> Result<()> {
-        if speed > self.max_speed {
+        let max_speed = self.metadata()?.max_speed;
+
+        if speed > max_speed {
             return Err(UsageError::ExcessSpeedLevel {
                 level: speed,
-                max_level: self.max_bright,
+                max_level: max_speed,
             }
             .into());
         }
 
-        let set_led_speed: Symbol<
-            unsafe extern "C" fn(
-                device_name: DeviceName,
-                led_index: LedIndex,
-                speed: SpeedLevel,
-            ) -> MysticLightSdkResult,
-        >;
-
-        unsafe {
-            let library = self.library.lock()?;
+        let result = self
+            .backend
+            .lock()?
+            .set_led_speed(&self.device_name, self.led_index, speed);
 
-            set_led_speed = library.get(b"MLAPI_SetLedSpeed")?;
-
-            MysticLightSDK::parse_result(set_led_speed(
-                self.device_name.as_ptr(),
-                self.led_index,
-                speed,
-            ))?;
-        }
+        self.known_state_mut().speed = result.is_ok().then_some(speed);
 
-        Ok(())
+        result
     }
 
     /// Set the whole state for the led
     ///
-    /// # Caveats
-    ///
-    /// Some of the styles do not support setting color for the led.
-    /// In this case this method will return `Err(CommonError::MysticLightSDKError(Timeout))` as this error is returned by the underlying dll
-    #[tracing::instrument(level = "debug", skip(self), fields(self.name = self.name))]
+    /// Parameters that equal to the last known state of the led are not sent, see [DeviceLed::set_state_forced].
+    /// Writes are not atomic, so the led might be left half-updated on failure. Use [DeviceLed::set_state_transactional] to avoid that
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
     pub fn set_state(&self, state: &DeviceLedState) -> Result<()> {
-        self.set_style(&state.style)?;
-        self.set_bright(state.bright)?;
-        self.set_speed(state.speed)?;
-        match self.set_color(&state.color) {
-            Ok(_) => (),
-            Err(CommonError::SdkError {
-                source: MysticLightSDKError::NotSupported,
-            }) => (),
-            error => return error,
-        };
+        self.write_state(state, false)
+    }
+
+    /// Forced version of the [DeviceLed::set_state]. Every parameter is sent to the sdk even if it equals to the last known state
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
+    pub fn set_state_forced(&self, state: &DeviceLedState) -> Result<()> {
+        self.write_state(state, true)
+    }
+
+    fn write_state(&self, state: &DeviceLedState, force: bool) -> Result<()> {
+        if self.known_state(force).style.as_ref() != Some(&state.style) {
+            self.set_style(&state.style)?;
+        }
+
+        let known_state = self.known_state(force);
+
+        if known_state.bright != Some(state.bright) {
+            self.set_bright(state.bright)?;
+        }
+
+        if known_state.speed != Some(state.speed) {
+            self.set_speed(state.speed)?;
+        }
+
+        if known_state.color.as_ref() != Some(&state.color) {
+            match self.set_color(&state.color) {
+                Ok(_) => (),
+                Err(CommonError::SdkError {
+                    source: MysticLightSDKError::NotSupported,
+                    ..
+                }) => (),
+                error => return error,
+            };
+        }
 
         Ok(())
     }
 
     /// Merge led current state with passed one i.e. applies only props that are Some() in passed argument
+    ///
+    /// Parameters that equal to the last known state of the led are not sent, see [DeviceLed::merge_with_state_forced]
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
     pub fn merge_with_state(&self, state: &DeviceLedStateInput) -> Result<()> {
-        if let Some(style) = &state.style {
+        self.write_merged_state(state, false)
+    }
+
+    /// Forced version of the [DeviceLed::merge_with_state]. Every passed parameter is sent to the sdk even if it equals to the last known state
+    #[tracing::instrument(level = "debug", skip(self), fields(self.device_name = self.device_name, self.led_index = self.led_index))]
+    pub fn merge_with_state_forced(&self, state: &DeviceLedStateInput) -> Result<()> {
+        self.write_merged_state(state, true)
+    }
+
+    fn write_merged_state(&self, state: &DeviceLedStateInput, force: bool) -> Result<()> {
+        let known_state = self.known_state(force);
+
+        if let Some(style) = state
+            .style
+            .as_ref()
+            .filter(|style| known_state.style.as_ref() != Some(style))
+        {
             self.set_style(style)?;
         }
 
-        if let Some(bright) = state.bright {
+        let known_state = self.known_state(force);
+
+        // style is taken from the last known state to not make an extra sdk call just for the warning
+        if let Some(style) = state.style.as_ref().or(known_state.style.as_ref()) {
+            self.warn_not_honoured(style, state);
+        }
+
+        if let Some(bright) = state
+            .bright
+            .filter(|bright| known_state.bright != Some(*bright))
+        {
             self.set_bright(bright)?;
         }
 
-        if let Some(speed) = state.speed {
+        if let Some(speed) = state
+            .speed
+            .filter(|speed| known_state.speed != Some(*speed))
+        {
             self.set_speed(speed)?;
         }
 
-        if let Some(color) = &state.color {
+        if let Some(color) = state
+            .color
+            .as_ref()
+            .filter(|color| known_state.color.as_ref() != Some(color))
+        {
             match self.set_color(color) {
                 Ok(_) => (),
                 Err(CommonError::SdkError {
                     source: MysticLightSDKError::NotSupported,
+                    ..
                 }) => (),
                 error => return error,
             };
@@ -514,6 +781,61 @@ impl DeviceLed {
 
         Ok(())
     }
+
+    /// Log the passed parameters that are not expected to be honoured by the style. Parameters are still sent to the sdk
+    fn warn_not_honoured(&self, style: &LedStyle, state: &DeviceLedStateInput) {
+        let Some(capabilities) = self
+            .probed_capabilities(style)
+            .or_else(|| style.capabilities())
+        else {
+            return;
+        };
+
+        let parameters = [
+            ("color", state.color.is_some(), capabilities.color),
+            ("bright", state.bright.is_some(), capabilities.bright),
+            ("speed", state.speed.is_some(), capabilities.speed),
+        ];
+
+        for (parameter, requested, honoured) in parameters {
+            if requested && !honoured {
+                tracing::warn!(device_name = self.device_name, led_index = self.led_index, %style, parameter, "Parameter is probably not honoured by the style");
+            }
+        }
+    }
+
+    /// Async version of the [DeviceLed::get_state]
+    ///
+    /// Sdk calls are executed on the separate thread, so the returned future doesn't block and may be awaited by any async runtime
+    pub async fn get_state_async(&self) -> Result<DeviceLedState> {
+        let led = self.clone();
+
+        self.backend.spawn_blocking(move || led.get_state()).await
+    }
+
+    /// Async version of the [DeviceLed::set_state]
+    ///
+    /// Sdk calls are executed on the separate thread, so the returned future doesn't block and may be awaited by any async runtime
+    pub async fn set_state_async(&self, state: &DeviceLedState) -> Result<()> {
+        let led = self.clone();
+        let state = state.clone();
+
+        self.backend
+            .spawn_blocking(move || led.set_state(&state))
+            .await
+    }
+
+    /// Async version of the [DeviceLed::merge_with_state]
+    ///
+    /// Sdk calls are executed on the separate thread, so the returned future doesn't block and may be awaited by any async runtime
+    pub async fn merge_with_state_async(&self, state: &DeviceLedStateInput) -> Result<()> {
+        let led = self.clone();
+        let state = state.clone();
+
+        self.backend
+            .spawn_blocking(move || led.merge_with_state(&state))
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -522,7 +844,7 @@ mod tests {
     #[cfg(feature = "serde")]
     fn device_led_state_serialize_deserialize() {
         use super::DeviceLedState;
-        use crate::Color;
+        use crate::{Color, LedStyle};
 
         let device_led_state = DeviceLedState {
             bright: 10,
@@ -532,7 +854,7 @@ mod tests {
                 green: 50,
                 blue: 100,
             },
-            style: String::from("led_style"),
+            style: LedStyle::from("led_style"),
         };
 
         let serialized_string = serde_json::to_string(&device_led_state).unwrap();
@@ -544,4 +866,217 @@ mod tests {
             device_led_state
         );
     }
+
+    #[test]
+    #[cfg(all(feature = "recording", feature = "simulated"))]
+    fn writes_only_changed_parameters() {
+        use super::{DeviceLedState, DeviceLedStateInput};
+        use crate::sdk::test_support::{simulated_backend, SharedBuffer};
+        use crate::{Color, LedStyle, MysticLightSDK, RecordingBackend};
+
+        /// returns names of the sdk write functions called since the previous call
+        fn take_writes(buffer: &SharedBuffer) -> Vec<String> {
+            buffer
+                .take()
+                .lines()
+                .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+                .map(|entry| entry["function"].as_str().unwrap().to_owned())
+                .filter(|function| function.starts_with("MLAPI_Set"))
+                .collect()
+        }
+
+        let buffer = SharedBuffer::default();
+        let sdk = MysticLightSDK::with_backend(RecordingBackend::new(
+            simulated_backend(),
+            buffer.clone(),
+        ))
+        .unwrap();
+        let led = sdk.devices()[0].led_by_name("JRGB1").unwrap().unwrap();
+        let mut state = DeviceLedState {
+            style: LedStyle::Breathing,
+            color: Color {
+                red: 1,
+                green: 2,
+                blue: 3,
+            },
+            bright: 3,
+            speed: 2,
+        };
+
+        // nothing is known before the first write
+        led.set_state(&state).unwrap();
+
+        assert_eq!(take_writes(&buffer).len(), 4);
+
+        led.set_state(&state).unwrap();
+
+        assert!(take_writes(&buffer).is_empty());
+
+        state.color.red = 100;
+        led.set_state(&state).unwrap();
+
+        assert_eq!(take_writes(&buffer), vec!["MLAPI_SetLedColor"]);
+
+        led.clone()
+            .merge_with_state(&DeviceLedStateInput {
+                style: Some(LedStyle::Breathing),
+                bright: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(take_writes(&buffer), vec!["MLAPI_SetLedBright"]);
+
+        // style change might reset other parameters
+        led.set_style(&LedStyle::NoAnimation).unwrap();
+        take_writes(&buffer);
+        state.bright = 1;
+        led.set_state(&state).unwrap();
+
+        assert_eq!(take_writes(&buffer).len(), 4);
+
+        led.set_state_forced(&state).unwrap();
+
+        assert_eq!(take_writes(&buffer).len(), 4);
+
+        led.forget_state();
+        led.get_state().unwrap();
+        led.set_state(&state).unwrap();
+
+        assert!(take_writes(&buffer).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn set_sub_led_colors() {
+        use crate::sdk::test_support::{find_led, simulated_sdk};
+        use crate::{Color, CommonError, MysticLightSDKError};
+
+        let sdk = simulated_sdk();
+        let led = find_led(&sdk, "Keyboard");
+        let red = Color {
+            red: 255,
+            green: 0,
+            blue: 0,
+        };
+
+        let colors = ["W", "A", "S", "D"]
+            .into_iter()
+            .map(|name| (String::from(name), red.clone()))
+            .collect();
+
+        led.set_sub_led_colors(&colors).unwrap();
+
+        let colors = [(String::from("Unknown"), red)].into_iter().collect();
+
+        assert!(matches!(
+            led.set_sub_led_colors(&colors),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::InvalidArgument,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn sub_led_writes_degrade_without_optional_functions() {
+        use crate::sdk::test_support::{find_led, simulated_sdk_without};
+        use crate::{Capability, Color, SubLedWriteMode};
+
+        let sdk = simulated_sdk_without(vec![Capability::SetLedColors, Capability::SetLedColorEx]);
+        let led = find_led(&sdk, "Keyboard");
+        let red = Color {
+            red: 255,
+            green: 0,
+            blue: 0,
+        };
+        let colors = [(String::from("W"), red.clone())].into_iter().collect();
+
+        led.set_sub_led_colors(&colors).unwrap();
+        led.sub_led("A")
+            .set_color(&red, SubLedWriteMode::Async, false)
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn sub_led_writes_fall_back_to_the_led_area() {
+        use crate::sdk::test_support::{find_led, simulated_sdk_without};
+        use crate::{Capability, Color, CommonError};
+
+        let sdk = simulated_sdk_without(vec![
+            Capability::SetLedColors,
+            Capability::SetLedColorEx,
+            Capability::SetLedColorSync,
+        ]);
+        let led = find_led(&sdk, "Keyboard");
+        let red = Color {
+            red: 255,
+            green: 0,
+            blue: 0,
+        };
+        let green = Color {
+            red: 0,
+            green: 255,
+            blue: 0,
+        };
+
+        let colors = [
+            (String::from("W"), red.clone()),
+            (String::from("A"), red.clone()),
+        ]
+        .into_iter()
+        .collect();
+
+        led.set_sub_led_colors(&colors).unwrap();
+
+        assert_eq!(led.get_state().unwrap().color, red);
+
+        let colors = [(String::from("W"), red), (String::from("A"), green)]
+            .into_iter()
+            .collect();
+
+        assert!(matches!(
+            led.set_sub_led_colors(&colors),
+            Err(CommonError::NotSupportedCapability {
+                capability: Capability::SetLedColors
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn lazy_leds_are_resolved_on_first_access() {
+        use crate::sdk::test_support::simulated_backend;
+        use crate::{Color, CommonError, MysticLightSDK, UsageError};
+
+        let sdk = MysticLightSDK::with_backend_lazy(simulated_backend()).unwrap();
+        let led = sdk.devices()[0].led(1).unwrap();
+
+        assert!(!led.is_resolved());
+
+        led.set_color(&Color {
+            red: 1,
+            green: 2,
+            blue: 3,
+        })
+        .unwrap();
+
+        assert!(!led.is_resolved());
+        assert!(!sdk.devices()[0].led(0).unwrap().is_resolved());
+
+        let clone = led.clone();
+
+        assert_eq!(clone.name(), "JRGB1");
+        assert!(led.is_resolved());
+        assert_eq!(led.max_bright(), 5);
+        assert!(matches!(
+            led.set_bright(6),
+            Err(CommonError::UsageError {
+                source: UsageError::ExcessBrightLevel { .. }
+            })
+        ));
+        assert!(!sdk.devices()[0].led(0).unwrap().is_resolved());
+    }
 }
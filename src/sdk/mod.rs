@@ -1,15 +1,42 @@
 //! Rust SDK wrapper for the [Mystic Light SDK](https://www.msi.com/Landing/mystic-light-rgb-gaming-pc/download)
 
+pub mod backend;
+#[cfg(feature = "cache")]
+pub mod cache;
 pub mod color;
 pub mod device;
+pub mod device_kind;
 pub mod error;
 pub mod led;
 pub mod mystic_light;
+#[cfg(feature = "openrgb")]
+pub mod openrgb;
+pub mod probe;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod style;
+pub mod sub_led;
+mod task;
+#[cfg(all(test, feature = "simulated"))]
+mod test_support;
+pub mod transaction;
 pub mod types;
 
+pub use backend::*;
+#[cfg(feature = "cache")]
+pub use cache::*;
 pub use color::*;
 pub use device::*;
+pub use device_kind::*;
 pub use error::*;
 pub use led::*;
 pub use mystic_light::*;
+#[cfg(feature = "openrgb")]
+pub use openrgb::*;
+pub use probe::*;
+#[cfg(feature = "remote")]
+pub use remote::*;
+pub use style::*;
+pub use sub_led::*;
+pub use transaction::*;
 pub use types::*;
@@ -1,74 +1,67 @@
-use std::{
-    collections::HashMap,
-    fmt::Debug,
-    ptr::null_mut,
-    sync::{Arc, Mutex},
-};
-
 #[cfg(feature = "async-graphql")]
-use either::Either;
-use libloading::{Library, Symbol};
-
-use crate::{winapi::FromSafeArray, DeviceTypes, LedCounts, MysticLightSdkResult};
+use std::sync::{Arc, Mutex};
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::backend::{Capabilities, DeviceInfo, LightingBackend, SharedBackend};
+#[cfg(feature = "cache")]
+use crate::cache::{hardware_signature, CacheError, Inventory, InventoryCache};
+#[cfg(windows)]
+use crate::LibraryBackend;
 #[cfg(feature = "async-graphql")]
-use crate::{DeviceMutation, SyncError};
+use crate::{Capability, DeviceKind, DeviceMutation, SyncError};
+use crate::{DeviceId, DeviceIndex, LedResolution, MysticLightSdkResult};
 
-use super::{
-    device::Device,
-    error::MysticLightSDKError,
-    types::{Filter, Result},
-};
+use super::task::spawn_blocking;
+#[cfg(feature = "async-graphql")]
+use super::types::Filter;
+use super::{device::Device, error::MysticLightSDKError, types::Result};
 
 /// used for filtering devices.
-/// Currently, supports only filtering by name
-#[derive(Default)]
-#[cfg_attr(feature = "async-graphql", derive(async_graphql::InputObject))]
+/// Supports filtering by type name and by kind, device should match both of the passed lists
+#[cfg(feature = "async-graphql")]
+#[derive(Default, async_graphql::InputObject)]
 struct DeviceFilter {
     names: Option<Vec<String>>,
+    kinds: Option<Vec<DeviceKind>>,
 }
 
+#[cfg(feature = "async-graphql")]
 impl Filter<&Device> for DeviceFilter {
     fn predicate(&self, device: &Device) -> bool {
-        match &self.names {
-            Some(names) => {
-                if names.is_empty() {
-                    return true;
-                }
-
-                names.iter().any(|name| name == device.name())
-            }
-            None => true,
-        }
+        let name_matches = match &self.names {
+            Some(names) if !names.is_empty() => names.iter().any(|name| name == device.name()),
+            _ => true,
+        };
+        let kind_matches = match &self.kinds {
+            Some(kinds) if !kinds.is_empty() => kinds.contains(device.kind()),
+            _ => true,
+        };
+
+        name_matches && kind_matches
     }
 }
 
 #[cfg(feature = "async-graphql")]
-fn filter_devices(
-    devices: &HashMap<String, Device>,
-    filter: DeviceFilter,
-) -> Either<impl Iterator<Item = &Device>, impl Iterator<Item = &Device>> {
-    match filter.names {
-        Some(names) => Either::Left(
-            names
-                .into_iter()
-                .filter_map(|led_name| devices.get(&led_name)),
-        ),
-        None => Either::Right(devices.values()),
-    }
+fn filter_devices(devices: &[Device], filter: DeviceFilter) -> impl Iterator<Item = &Device> {
+    devices
+        .iter()
+        .filter(move |device| filter.predicate(device))
 }
 
 /// Rust Wrapper for the underlying Mystic Light SDK
 pub struct MysticLightSDK {
-    library: Arc<Mutex<Library>>,
-    devices: HashMap<String, Device>,
-    #[cfg(feature = "async-graphql")]
-    lib_path: String,
+    backend: SharedBackend,
+    capabilities: Capabilities,
+    devices: Vec<Device>,
+    resolution: LedResolution,
 }
 
 impl Debug for MysticLightSDK {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MysticLightSDK")
+            .field("capabilities", &self.capabilities)
             .field("devices", &self.devices)
+            .field("resolution", &self.resolution)
             .finish()
     }
 }
@@ -78,9 +71,19 @@ impl Debug for MysticLightSDK {
 #[async_graphql::Object]
 impl MysticLightSDK {
     /// returns Mystic Light devices
-    async fn devices(&self, #[graphql(default)] filter: DeviceFilter) -> Vec<&Device> {
+    #[graphql(name = "devices")]
+    async fn async_graphql_devices(
+        &self,
+        #[graphql(default)] filter: DeviceFilter,
+    ) -> Vec<&Device> {
         filter_devices(&self.devices, filter).collect()
     }
+
+    /// returns optional sdk functions available in the backend
+    #[graphql(name = "capabilities")]
+    async fn async_graphql_capabilities(&self) -> Vec<Capability> {
+        self.capabilities.iter().collect()
+    }
 }
 
 /// Mutation wrapper for sdk
@@ -96,7 +99,7 @@ impl MysticLightSDKMutation {
     async fn devices(
         &self,
         #[graphql(default)] filter: DeviceFilter,
-    ) -> Result<Vec<DeviceMutation>> {
+    ) -> Result<Vec<DeviceMutation<'_>>> {
         let devices = filter_devices(&self.0.devices, filter)
             .map(DeviceMutation)
             .collect();
@@ -116,10 +119,16 @@ impl MysticLightGraphqlWrapper {
         Ok(Arc::clone(&sdk))
     }
 
-    fn reload(&self) -> Result<()> {
-        let mut sdk = self.0.lock()?;
+    async fn reload(&self) -> Result<()> {
+        let sdk = self.sdk()?;
+        let backend = sdk.backend.clone();
+        let resolution = sdk.resolution;
+        let reloaded = sdk
+            .backend
+            .spawn_blocking(move || MysticLightSDK::from_shared_backend(backend, resolution))
+            .await?;
 
-        *sdk = Arc::new(MysticLightSDK::new(&sdk.lib_path)?);
+        *self.0.lock()? = Arc::new(reloaded);
 
         Ok(())
     }
@@ -156,7 +165,7 @@ impl MysticLightGraphqlMutation {
 
     /// Full reload of Mystic Light SDK to get most-fresh hardware data
     async fn reload(&self) -> Result<bool> {
-        self.0.reload()?;
+        self.0.reload().await?;
 
         Ok(true)
     }
@@ -189,82 +198,322 @@ impl MysticLightSDK {
             -101 => Err(MysticLightSDKError::InvalidArgument),
             -102 => Err(MysticLightSDKError::DeviceNotFound),
             -103 => Err(MysticLightSDKError::NotSupported),
-            _ => Err(MysticLightSDKError::Unknown),
+            code => Err(MysticLightSDKError::Unknown { code }),
         }
     }
 
     /// Initialize MysticLight SDK with passed path to the dll file
     ///
     /// **You must pass valid dll based on the os architecture**
+    #[cfg(windows)]
     #[tracing::instrument(level = "debug")]
     pub fn new(lib_path: &str) -> Result<Self> {
-        let library;
+        Self::with_backend(LibraryBackend::new(lib_path)?)
+    }
 
-        unsafe {
-            library = Library::new(lib_path)?;
+    /// Initialize MysticLight SDK with the passed backend
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn with_backend<B: LightingBackend + 'static>(backend: B) -> Result<Self> {
+        Self::from_shared_backend(SharedBackend::new(backend), LedResolution::Eager)
+    }
 
-            let initialize: Symbol<unsafe extern "C" fn() -> MysticLightSdkResult> =
-                library.get(b"MLAPI_Initialize")?;
+    /// Initialize MysticLight SDK with the passed backend without fetching metadata of the leds.
+    /// Metadata of the led as well as friendly name and sub-leds of the device are fetched on the first access, see [LedResolution::Lazy]
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn with_backend_lazy<B: LightingBackend + 'static>(backend: B) -> Result<Self> {
+        Self::from_shared_backend(SharedBackend::new(backend), LedResolution::Lazy)
+    }
+
+    /// Async version of the [MysticLightSDK::new]
+    ///
+    /// Dll is loaded and initialized on the separate thread, so the returned future doesn't block and may be awaited by any async runtime
+    #[cfg(windows)]
+    pub async fn new_async(lib_path: &str) -> Result<Self> {
+        let lib_path = lib_path.to_owned();
+
+        spawn_blocking(move || Self::new(&lib_path)).await
+    }
 
-            MysticLightSDK::parse_result(initialize())?;
+    /// Async version of the [MysticLightSDK::with_backend]
+    ///
+    /// Backend is initialized on the separate thread, so the returned future doesn't block and may be awaited by any async runtime
+    pub async fn with_backend_async<B: LightingBackend + 'static>(backend: B) -> Result<Self> {
+        spawn_blocking(move || Self::with_backend(backend)).await
+    }
+
+    /// Async version of the [MysticLightSDK::with_backend_lazy]
+    pub async fn with_backend_lazy_async<B: LightingBackend + 'static>(backend: B) -> Result<Self> {
+        spawn_blocking(move || Self::with_backend_lazy(backend)).await
+    }
+
+    /// Initialize MysticLight SDK with the passed backend using the inventory cache to skip querying every led
+    ///
+    /// If the cached inventory has the same [hardware_signature] as the connected hardware, devices are created from the cache as is.
+    /// Use [MysticLightSDK::revalidate_cache] or [MysticLightSDK::spawn_cache_revalidation] to pick up changes of the leds on the next start.
+    /// Otherwise devices are resolved from the sdk and stored in the cache. Cache failures are logged and never fail the initialization
+    #[cfg(feature = "cache")]
+    #[tracing::instrument(level = "debug", skip_all, fields(cache = ?cache.path()))]
+    pub fn with_backend_cached<B: LightingBackend + 'static>(
+        backend: B,
+        cache: InventoryCache,
+    ) -> Result<Self> {
+        let backend = SharedBackend::new(backend);
+        let capabilities = Self::initialize(&backend)?;
+
+        let devices_info = backend.lock()?.get_device_info()?;
+        let signature = hardware_signature(&devices_info);
+
+        let cached = match cache.load() {
+            Ok(inventory) => inventory.filter(|inventory| inventory.signature == signature),
+            Err(error) => {
+                tracing::warn!(%error, "Cannot load inventory cache");
+
+                None
+            }
+        };
+
+        let devices = match cached {
+            Some(inventory) => {
+                tracing::debug!(signature, "Devices are loaded from the inventory cache");
+
+                Self::devices_from_inventory(&backend, &inventory)
+            }
+            None => {
+                let devices = Self::create_devices(&backend, devices_info, LedResolution::Eager)?;
+
+                if let Err(error) = Inventory::from_devices(&devices)
+                    .map_err(CacheError::from)
+                    .and_then(|inventory| cache.store(&inventory))
+                {
+                    tracing::warn!(%error, "Cannot store inventory cache");
+                }
+
+                devices
+            }
+        };
+
+        Ok(MysticLightSDK {
+            backend,
+            capabilities,
+            devices,
+            resolution: LedResolution::Eager,
+        })
+    }
+
+    /// Async version of the [MysticLightSDK::with_backend_cached]
+    #[cfg(feature = "cache")]
+    pub async fn with_backend_cached_async<B: LightingBackend + 'static>(
+        backend: B,
+        cache: InventoryCache,
+    ) -> Result<Self> {
+        spawn_blocking(move || Self::with_backend_cached(backend, cache)).await
+    }
+
+    /// Resolve devices from the sdk and store them in the cache if they differ from the cached ones.
+    /// Devices of this instance are not changed, use [MysticLightSDK::reload] for that
+    ///
+    /// returns whether the cache was updated
+    #[cfg(feature = "cache")]
+    #[tracing::instrument(level = "debug", skip_all, fields(cache = ?cache.path()))]
+    pub fn revalidate_cache(
+        &self,
+        cache: &InventoryCache,
+    ) -> std::result::Result<bool, CacheError> {
+        Self::revalidate(&self.backend, cache)
+    }
+
+    #[cfg(feature = "cache")]
+    fn revalidate(
+        backend: &SharedBackend,
+        cache: &InventoryCache,
+    ) -> std::result::Result<bool, CacheError> {
+        let inventory =
+            Inventory::from_devices(&Self::resolve_devices(backend, LedResolution::Eager)?)?;
+
+        if cache.load().ok().flatten().as_ref() == Some(&inventory) {
+            return Ok(false);
         }
 
-        let library = Arc::new(Mutex::new(library));
-        let devices = Self::resolve_devices(&library)?;
+        cache.store(&inventory)?;
+
+        Ok(true)
+    }
+
+    /// Run [MysticLightSDK::revalidate_cache] on the separate thread. Join the returned handle to get its result
+    ///
+    /// # Caveats
+    ///
+    /// Revalidation resolves every led from the sdk, so it holds the backend lock for every call and
+    /// the other calls of this instance wait for it
+    #[cfg(feature = "cache")]
+    #[tracing::instrument(level = "debug", skip_all, fields(cache = ?cache.path()))]
+    pub fn spawn_cache_revalidation(
+        &self,
+        cache: InventoryCache,
+    ) -> std::io::Result<std::thread::JoinHandle<std::result::Result<bool, CacheError>>> {
+        let backend = self.backend.clone();
+
+        std::thread::Builder::new()
+            .name(String::from("mystic-light-sdk-cache"))
+            .spawn(move || {
+                let result = Self::revalidate(&backend, &cache);
+
+                match &result {
+                    Ok(updated) => tracing::debug!(updated, "Inventory cache is revalidated"),
+                    Err(error) => tracing::warn!(%error, "Cannot revalidate inventory cache"),
+                }
+
+                result
+            })
+    }
+
+    #[cfg(feature = "cache")]
+    fn devices_from_inventory(backend: &SharedBackend, inventory: &Inventory) -> Vec<Device> {
+        inventory
+            .devices
+            .iter()
+            .map(|device| Device::from_inventory(backend.clone(), device))
+            .collect()
+    }
+
+    fn from_shared_backend(backend: SharedBackend, resolution: LedResolution) -> Result<Self> {
+        let capabilities = Self::initialize(&backend)?;
+        let devices = Self::resolve_devices(&backend, resolution)?;
 
         Ok(MysticLightSDK {
-            library,
+            backend,
+            capabilities,
             devices,
-            #[cfg(feature = "async-graphql")]
-            lib_path: lib_path.to_owned(),
+            resolution,
         })
     }
 
-    /// returns Iterator iver Mystic Light devices
+    fn initialize(backend: &SharedBackend) -> Result<Capabilities> {
+        let mut backend_instance = backend.lock()?;
+        backend_instance.initialize()?;
+        let capabilities = backend_instance.capabilities();
+        drop(backend_instance);
+
+        let missing: Vec<_> = capabilities.missing().collect();
+        if !missing.is_empty() {
+            tracing::warn!(?missing, "Some of the sdk functions are not available");
+        }
+
+        Ok(capabilities)
+    }
+
+    /// returns optional sdk functions available in the backend.
+    /// Features relying on the missing functions either degrade gracefully or return [CommonError::NotSupportedCapability](crate::CommonError::NotSupportedCapability)
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// returns backend shared by the sdk and all of its devices
+    #[cfg(feature = "remote")]
+    pub(crate) fn shared_backend(&self) -> &SharedBackend {
+        &self.backend
+    }
+
+    /// returns human-readable product names of all the devices with the passed type name e.g. `MSI_MB`
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn device_names(&self, device_type: &str) -> Result<Vec<String>> {
+        self.backend.lock()?.get_device_name(device_type)
+    }
+
+    /// Async version of the [MysticLightSDK::device_names]
+    pub async fn device_names_async(&self, device_type: &str) -> Result<Vec<String>> {
+        let backend = self.backend.clone();
+        let device_type = device_type.to_owned();
+
+        self.backend
+            .spawn_blocking(move || backend.lock()?.get_device_name(&device_type))
+            .await
+    }
+
+    /// returns Iterator over Mystic Light devices in the order they are enumerated by the sdk
     pub fn devices_iter(&self) -> impl Iterator<Item = &Device> {
-        self.devices.values()
+        self.devices.iter()
+    }
+
+    /// returns Mystic Light devices in the order they are enumerated by the sdk
+    pub fn devices(&self) -> &[Device] {
+        &self.devices
+    }
+
+    /// returns device by its unique identifier
+    pub fn device(&self, id: &DeviceId) -> Option<&Device> {
+        self.devices
+            .iter()
+            .find(|device| device.name() == id.name && device.index() == id.index)
     }
 
     /// reload cached devices info
     #[tracing::instrument(level = "debug", skip_all)]
     pub fn reload(&mut self) -> Result<()> {
-        self.devices = Self::resolve_devices(&self.library)?;
+        self.devices = Self::resolve_devices(&self.backend, self.resolution)?;
+
+        Ok(())
+    }
+
+    /// Async version of the [MysticLightSDK::reload]
+    pub async fn reload_async(&mut self) -> Result<()> {
+        let backend = self.backend.clone();
+        let resolution = self.resolution;
+
+        self.devices = self
+            .backend
+            .spawn_blocking(move || Self::resolve_devices(&backend, resolution))
+            .await?;
 
         Ok(())
     }
 
     #[tracing::instrument(level = "debug", skip_all)]
-    fn resolve_devices(library: &Arc<Mutex<Library>>) -> Result<HashMap<String, Device>> {
-        let mut dev_type: DeviceTypes = null_mut();
-        let mut led_count: LedCounts = null_mut();
+    fn resolve_devices(backend: &SharedBackend, resolution: LedResolution) -> Result<Vec<Device>> {
+        let devices_info = backend.lock()?.get_device_info()?;
 
-        let dev_type_ptr: *mut DeviceTypes = &mut dev_type;
-        let led_count_ptr: *mut LedCounts = &mut led_count;
+        Self::create_devices(backend, devices_info, resolution)
+    }
 
-        unsafe {
-            let library = library.lock()?;
+    fn create_devices(
+        backend: &SharedBackend,
+        devices_info: Vec<DeviceInfo>,
+        resolution: LedResolution,
+    ) -> Result<Vec<Device>> {
+        let mut type_devices: HashMap<String, u32> = HashMap::new();
 
-            let api_get_info: Symbol<
-                unsafe extern "C" fn(
-                    dev_type: *mut DeviceTypes,
-                    led_count: *mut LedCounts,
-                ) -> MysticLightSdkResult,
-            > = library.get(b"MLAPI_GetDeviceInfo")?;
+        for device_info in &devices_info {
+            *type_devices.entry(device_info.name.clone()).or_default() += 1;
+        }
 
-            MysticLightSDK::parse_result(api_get_info(dev_type_ptr, led_count_ptr))?
+        for (name, count) in type_devices.iter().filter(|(_, count)| **count > 1) {
+            tracing::warn!(
+                name,
+                count,
+                "Multiple devices have the same type. Only leds of the first device are exposed and sub-leds are not resolved"
+            );
         }
 
-        let devices_names: Vec<String> = Vec::from_safearray(dev_type);
-        let leds: Vec<String> = Vec::from_safearray(led_count);
+        // next device index for every device type
+        let mut type_indexes: HashMap<String, DeviceIndex> = HashMap::new();
 
-        let devices = devices_names
+        let devices = devices_info
             .into_iter()
-            .zip(leds)
-            .map(|(device_name, led_count)| {
-                let led_count: u32 = led_count.parse().expect("Cannot parse led count str");
-                let device = Device::new(Arc::clone(library), device_name, led_count)?;
-
-                Ok((device.name().to_owned(), device))
+            .map(|device_info| {
+                let shared_type = type_devices[&device_info.name] > 1;
+                let index = type_indexes.entry(device_info.name.clone()).or_default();
+                let device = Device::new(
+                    backend.clone(),
+                    device_info.name,
+                    *index,
+                    device_info.led_count,
+                    shared_type,
+                    resolution,
+                )?;
+
+                *index += 1;
+
+                Ok(device)
             })
             .collect::<Result<_>>()?;
 
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+use std::io;
+
+use super::protocol::{PacketReader, PacketWriter};
+use crate::{
+    Color, Device, DeviceKind, DeviceLed, DeviceLedState, LedStyle, Result, SubLedWriteMode,
+};
+
+const MODE_FLAG_HAS_SPEED: u32 = 1 << 0;
+const MODE_FLAG_HAS_BRIGHTNESS: u32 = 1 << 4;
+const MODE_FLAG_HAS_PER_LED_COLOR: u32 = 1 << 5;
+
+const MODE_COLORS_NONE: u32 = 0;
+const MODE_COLORS_PER_LED: u32 = 1;
+
+const ZONE_TYPE_SINGLE: i32 = 0;
+const ZONE_TYPE_LINEAR: i32 = 1;
+
+/// Styles that are used for the OpenRGB custom mode in the order of preference
+const CUSTOM_MODE_STYLES: [&str; 2] = ["NoAnimation", "Steady"];
+
+/// Style that turns the led off and therefore has no colors
+const OFF_STYLE: &str = "Off";
+
+/// returns OpenRGB device type for the Mystic Light device kind
+fn device_type(kind: &DeviceKind) -> i32 {
+    match kind {
+        DeviceKind::Motherboard => 0,
+        DeviceKind::Dram => 1,
+        DeviceKind::Gpu => 2,
+        DeviceKind::Cooler => 3,
+        DeviceKind::LightStrip => 4,
+        DeviceKind::Keyboard => 5,
+        DeviceKind::Mouse => 6,
+        DeviceKind::Mousepad => 7,
+        DeviceKind::Headset => 8,
+        DeviceKind::HeadsetStand => 9,
+        DeviceKind::Speaker => 12,
+        // light is the most generic device type that is known for all of the protocol versions
+        _ => 11,
+    }
+}
+
+/// Mode update sent by the OpenRGB client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ModeUpdate {
+    pub index: usize,
+    pub speed: u32,
+    /// brightness is the part of the mode since protocol version 3
+    pub brightness: Option<u32>,
+}
+
+impl ModeUpdate {
+    /// Parse data of the `RGBCONTROLLER_UPDATEMODE` packet
+    pub(crate) fn parse(data: &[u8], version: u32) -> io::Result<Self> {
+        let mut reader = PacketReader::new(data);
+
+        let _data_size = reader.u32()?;
+        let index = reader.i32()?;
+        let _name = reader.string()?;
+        let _value = reader.i32()?;
+        let _flags = reader.u32()?;
+        let _speed_min = reader.u32()?;
+        let _speed_max = reader.u32()?;
+
+        if version >= 3 {
+            let _brightness_min = reader.u32()?;
+            let _brightness_max = reader.u32()?;
+        }
+
+        let _colors_min = reader.u32()?;
+        let _colors_max = reader.u32()?;
+        let speed = reader.u32()?;
+        let brightness = if version >= 3 {
+            Some(reader.u32()?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            index: usize::try_from(index).unwrap_or(usize::MAX),
+            speed,
+            brightness,
+        })
+    }
+}
+
+/// Mystic Light device represented as the OpenRGB controller
+///
+/// Every led of the device becomes the zone. Zone has single led if the device has no sub-leds,
+/// otherwise it has led for every sub-led. Modes are the styles supported by any of the device leds in the sdk order
+pub(crate) struct Controller<'a> {
+    device: &'a Device,
+    zones: Vec<&'a DeviceLed>,
+    modes: Vec<&'a LedStyle>,
+}
+
+impl<'a> Controller<'a> {
+    pub(crate) fn new(device: &'a Device) -> Self {
+        let zones: Vec<_> = device.leds_iter().collect();
+
+        let mut modes: Vec<&LedStyle> = Vec::new();
+
+        for style in zones.iter().flat_map(|led| led.supported_styles()) {
+            if !modes.contains(&style) {
+                modes.push(style);
+            }
+        }
+
+        Self {
+            device,
+            zones,
+            modes,
+        }
+    }
+
+    fn zone_size(&self) -> usize {
+        self.device.sub_leds().len().max(1)
+    }
+
+    fn zone_leds(&self, zone: &DeviceLed) -> Vec<String> {
+        match self.device.sub_leds() {
+            [] => vec![zone.name().to_owned()],
+            sub_leds => sub_leds.to_vec(),
+        }
+    }
+
+    /// Serialize controller data in the format of the passed protocol version
+    #[tracing::instrument(level = "debug", skip(self), fields(device = %self.device.id()))]
+    pub(crate) fn describe(&self, version: u32) -> Vec<u8> {
+        let states: Vec<Option<DeviceLedState>> = self
+            .zones
+            .iter()
+            .map(|led| {
+                led.get_state()
+                    .map_err(
+                        |error| tracing::warn!(led = led.name(), %error, "Cannot get led state"),
+                    )
+                    .ok()
+            })
+            .collect();
+        let active_state = states.iter().flatten().next();
+        let active_mode = active_state
+            .and_then(|state| self.modes.iter().position(|mode| **mode == state.style))
+            .unwrap_or(0);
+
+        let mut data = PacketWriter::default();
+
+        data.i32(device_type(self.device.kind()))
+            .string(self.device.friendly_name());
+
+        if version >= 1 {
+            data.string("MSI");
+        }
+
+        data.string(&format!("Mystic Light {}", self.device.name()))
+            .string("")
+            .string("")
+            .string(&format!("Mystic Light: {}", self.device.id()));
+
+        data.u16(self.modes.len() as u16).i32(active_mode as i32);
+
+        for (index, mode) in self.modes.iter().enumerate() {
+            let leds: Vec<_> = self
+                .zones
+                .iter()
+                .filter_map(|led| Some((led, led.style_capabilities(mode)?)))
+                .collect();
+            let max_speed = leds
+                .iter()
+                .filter(|(_, capabilities)| capabilities.speed)
+                .map(|(led, _)| led.max_speed())
+                .max()
+                .unwrap_or(0);
+            let max_bright = leds
+                .iter()
+                .filter(|(_, capabilities)| capabilities.bright)
+                .map(|(led, _)| led.max_bright())
+                .max()
+                .unwrap_or(0);
+            let has_color = leds.iter().any(|(_, capabilities)| capabilities.color);
+            let (speed, bright) = match active_state {
+                Some(state) if index == active_mode => (state.speed, state.bright),
+                _ => (0, max_bright),
+            };
+
+            let mut flags = 0;
+
+            if max_speed > 0 {
+                flags |= MODE_FLAG_HAS_SPEED;
+            }
+
+            if max_bright > 0 {
+                flags |= MODE_FLAG_HAS_BRIGHTNESS;
+            }
+
+            let color_mode = if !has_color || mode.as_str() == OFF_STYLE {
+                MODE_COLORS_NONE
+            } else {
+                flags |= MODE_FLAG_HAS_PER_LED_COLOR;
+                MODE_COLORS_PER_LED
+            };
+
+            data.string(mode.as_str())
+                .i32(index as i32)
+                .u32(flags)
+                .u32(0)
+                .u32(max_speed);
+
+            if version >= 3 {
+                data.u32(0).u32(max_bright);
+            }
+
+            data.u32(0).u32(0).u32(speed);
+
+            if version >= 3 {
+                data.u32(bright);
+            }
+
+            // no direction, no mode specific colors
+            data.u32(0).u32(color_mode).u16(0);
+        }
+
+        let zone_size = self.zone_size() as u32;
+        let zone_type = if self.device.sub_leds().is_empty() {
+            ZONE_TYPE_SINGLE
+        } else {
+            ZONE_TYPE_LINEAR
+        };
+
+        data.u16(self.zones.len() as u16);
+
+        for zone in &self.zones {
+            data.string(zone.name())
+                .i32(zone_type)
+                .u32(zone_size)
+                .u32(zone_size)
+                .u32(zone_size)
+                // no matrix map
+                .u16(0);
+        }
+
+        let leds: Vec<_> = self
+            .zones
+            .iter()
+            .flat_map(|zone| self.zone_leds(zone))
+            .collect();
+
+        data.u16(leds.len() as u16);
+
+        for (index, led) in leds.iter().enumerate() {
+            data.string(led).u32(index as u32);
+        }
+
+        data.u16(leds.len() as u16);
+
+        for state in &states {
+            let color = state
+                .as_ref()
+                .map(|state| state.color.clone())
+                .unwrap_or(Color {
+                    red: 0,
+                    green: 0,
+                    blue: 0,
+                });
+
+            for _ in 0..self.zone_size() {
+                data.color(&color);
+            }
+        }
+
+        let mut packet = PacketWriter::default();
+
+        packet.u32(data.len() as u32 + 4).bytes(&data.into_inner());
+
+        packet.into_inner()
+    }
+
+    /// Set colors of all of the leds of the controller
+    pub(crate) fn update_leds(&self, colors: &[Color]) {
+        for (index, colors) in colors.chunks(self.zone_size()).enumerate() {
+            self.update_zone_leds(index, colors);
+        }
+    }
+
+    /// Set colors of the leds of the single zone
+    #[tracing::instrument(level = "debug", skip(self, colors), fields(device = %self.device.id()))]
+    pub(crate) fn update_zone_leds(&self, zone: usize, colors: &[Color]) {
+        let Some(led) = self.zones.get(zone) else {
+            tracing::warn!("Zone doesn't exist");
+
+            return;
+        };
+
+        let result = match (self.device.sub_leds(), colors.first()) {
+            (_, None) => Ok(()),
+            ([], Some(color)) => led.set_color(color),
+            (sub_leds, _) => {
+                let colors: HashMap<_, _> = sub_leds
+                    .iter()
+                    .cloned()
+                    .zip(colors.iter().cloned())
+                    .collect();
+
+                led.set_sub_led_colors(&colors)
+            }
+        };
+
+        log_failure(led, result);
+    }
+
+    /// Set color of the single led of the controller
+    #[tracing::instrument(level = "debug", skip(self), fields(device = %self.device.id()))]
+    pub(crate) fn update_single_led(&self, index: usize, color: &Color) {
+        let zone_size = self.zone_size();
+
+        let Some(led) = self.zones.get(index / zone_size) else {
+            tracing::warn!("Led doesn't exist");
+
+            return;
+        };
+
+        let result = match self.device.sub_leds() {
+            [] => led.set_color(color),
+            sub_leds => led.set_sub_led_color(
+                &sub_leds[index % zone_size],
+                color,
+                SubLedWriteMode::Async,
+                false,
+            ),
+        };
+
+        log_failure(led, result);
+    }
+
+    /// Apply mode to all of the leds that support its style
+    #[tracing::instrument(level = "debug", skip(self), fields(device = %self.device.id()))]
+    pub(crate) fn update_mode(&self, update: &ModeUpdate) {
+        let Some(style) = self.modes.get(update.index) else {
+            tracing::warn!("Mode doesn't exist");
+
+            return;
+        };
+
+        for led in self.zones.iter().filter(|led| led.supports_style(style)) {
+            let result = led.set_style(style).and_then(|_| {
+                if led.max_speed() > 0 {
+                    led.set_speed(update.speed.min(led.max_speed()))?;
+                }
+
+                match update.brightness {
+                    Some(brightness) if led.max_bright() > 0 => {
+                        led.set_bright(brightness.min(led.max_bright()))
+                    }
+                    _ => Ok(()),
+                }
+            });
+
+            log_failure(led, result);
+        }
+    }
+
+    /// Switch leds to the static style so they accept direct colors
+    #[tracing::instrument(level = "debug", skip(self), fields(device = %self.device.id()))]
+    pub(crate) fn set_custom_mode(&self) {
+        for led in &self.zones {
+            let style = CUSTOM_MODE_STYLES
+                .into_iter()
+                .map(LedStyle::from)
+                .find(|style| led.supports_style(style));
+
+            if let Some(style) = style {
+                log_failure(led, led.set_style(&style));
+            }
+        }
+    }
+}
+
+fn log_failure(led: &DeviceLed, result: Result<()>) {
+    if let Err(error) = result {
+        tracing::warn!(led = led.name(), %error, "Cannot apply OpenRGB update");
+    }
+}
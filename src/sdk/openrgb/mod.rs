@@ -0,0 +1,166 @@
+//! Server of the OpenRGB SDK network protocol
+//!
+//! [OpenRgbServer] exposes devices of the [MysticLightSDK](crate::MysticLightSDK) as OpenRGB controllers,
+//! so any OpenRGB client is able to control MSI hardware through this crate.
+//! Supports protocol versions up to the [OPENRGB_PROTOCOL_VERSION]
+
+mod controller;
+mod protocol;
+mod server;
+
+pub use server::*;
+
+use crate::CommonError;
+
+/// Port used by the OpenRGB SDK server by default
+pub const OPENRGB_DEFAULT_PORT: u16 = 6742;
+
+/// Max version of the OpenRGB SDK protocol supported by the server
+pub const OPENRGB_PROTOCOL_VERSION: u32 = 3;
+
+fn network_error(error: impl std::fmt::Display) -> CommonError {
+    CommonError::RemoteError {
+        message: error.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use std::net::TcpStream;
+    use std::thread;
+
+    use super::protocol::*;
+    use super::*;
+    use crate::sdk::test_support::{find_led, simulated_backend};
+    use crate::{Color, MysticLightSDK, SdkHandle};
+
+    /// Starts server and returns sdk that shares the backend with the server
+    fn start_server() -> (MysticLightSDK, TcpStream) {
+        let handle = SdkHandle::spawn(|| Ok(simulated_backend())).unwrap();
+        let sdk = MysticLightSDK::with_backend(handle.clone()).unwrap();
+        let server = OpenRgbServer::bind(sdk, "127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+
+        thread::spawn(move || server.run());
+
+        (
+            MysticLightSDK::with_backend(handle).unwrap(),
+            TcpStream::connect(addr).unwrap(),
+        )
+    }
+
+    fn request(stream: &mut TcpStream, device_index: u32, id: u32, data: PacketWriter) -> Vec<u8> {
+        write_packet(stream, &Packet::new(device_index, id, data.into_inner())).unwrap();
+
+        let response = read_packet(stream).unwrap().unwrap();
+
+        assert_eq!((response.device_index, response.id), (device_index, id));
+
+        response.data
+    }
+
+    fn send(stream: &mut TcpStream, device_index: u32, id: u32, data: PacketWriter) {
+        write_packet(stream, &Packet::new(device_index, id, data.into_inner())).unwrap();
+    }
+
+    fn u32_data(value: u32) -> PacketWriter {
+        let mut data = PacketWriter::default();
+        data.u32(value);
+        data
+    }
+
+    fn color(red: u32, green: u32, blue: u32) -> Color {
+        Color { red, green, blue }
+    }
+
+    #[test]
+    fn exposes_devices_as_controllers() {
+        let (_, mut stream) = start_server();
+
+        let version = request(&mut stream, 0, REQUEST_PROTOCOL_VERSION, u32_data(3));
+        assert_eq!(
+            PacketReader::new(&version).u32().unwrap(),
+            OPENRGB_PROTOCOL_VERSION
+        );
+
+        let count = request(
+            &mut stream,
+            0,
+            REQUEST_CONTROLLER_COUNT,
+            PacketWriter::default(),
+        );
+        assert_eq!(PacketReader::new(&count).u32().unwrap(), 3);
+
+        let data = request(&mut stream, 0, REQUEST_CONTROLLER_DATA, u32_data(3));
+        let mut reader = PacketReader::new(&data);
+
+        assert_eq!(reader.u32().unwrap() as usize, data.len());
+        assert_eq!(reader.i32().unwrap(), 0);
+        assert_eq!(reader.string().unwrap(), "MSI MPG Z690 CARBON WIFI");
+        assert_eq!(reader.string().unwrap(), "MSI");
+        assert_eq!(reader.string().unwrap(), "Mystic Light MSI_MB");
+        reader.string().unwrap();
+        reader.string().unwrap();
+        reader.string().unwrap();
+
+        assert_eq!(reader.u16().unwrap(), 4);
+        // active mode is the style of the first led
+        assert_eq!(reader.i32().unwrap(), 0);
+        assert_eq!(reader.string().unwrap(), "NoAnimation");
+    }
+
+    #[test]
+    fn updates_leds_and_modes() {
+        let (sdk, mut stream) = start_server();
+        let led = |name: &str| find_led(&sdk, name);
+
+        request(&mut stream, 0, REQUEST_PROTOCOL_VERSION, u32_data(3));
+
+        // leds of the MSI_MB are JRAINBOW1 and JRGB1
+        let mut data = PacketWriter::default();
+        data.u32(14)
+            .u16(2)
+            .color(&color(1, 2, 3))
+            .color(&color(4, 5, 6));
+        send(&mut stream, 0, RGBCONTROLLER_UPDATELEDS, data);
+
+        // mode "Flashing" with speed 1 and brightness 2
+        let mut data = PacketWriter::default();
+        data.u32(0)
+            .i32(2)
+            .string("Flashing")
+            .i32(2)
+            .u32(0)
+            .u32(0)
+            .u32(2)
+            .u32(0)
+            .u32(5)
+            .u32(0)
+            .u32(0)
+            .u32(1)
+            .u32(2)
+            .u32(0)
+            .u32(1)
+            .u16(0);
+        send(&mut stream, 0, RGBCONTROLLER_UPDATEMODE, data);
+
+        // requests are handled in order, so the response means all of the updates are applied
+        request(
+            &mut stream,
+            0,
+            REQUEST_CONTROLLER_COUNT,
+            PacketWriter::default(),
+        );
+
+        let jrainbow = led("JRAINBOW1").get_state().unwrap();
+
+        assert_eq!(jrainbow.color, color(1, 2, 3));
+        assert_eq!(jrainbow.style, "Flashing");
+        assert_eq!((jrainbow.speed, jrainbow.bright), (1, 2));
+
+        let jrgb = led("JRGB1").get_state().unwrap();
+
+        assert_eq!(jrgb.color, color(4, 5, 6));
+        assert_eq!(jrgb.style, "Breathing");
+    }
+}
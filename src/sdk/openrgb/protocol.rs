@@ -0,0 +1,274 @@
+use std::io::{self, Read, Write};
+
+use crate::Color;
+
+/// Magic bytes every packet starts with
+const MAGIC: &[u8; 4] = b"ORGB";
+
+/// Size of the packet header: magic, device index, packet id and data size
+const HEADER_SIZE: usize = 16;
+
+/// Max size of the packet data to protect from the malformed packets
+const MAX_DATA_SIZE: u32 = 1024 * 1024;
+
+pub(crate) const REQUEST_CONTROLLER_COUNT: u32 = 0;
+pub(crate) const REQUEST_CONTROLLER_DATA: u32 = 1;
+pub(crate) const REQUEST_PROTOCOL_VERSION: u32 = 40;
+pub(crate) const SET_CLIENT_NAME: u32 = 50;
+pub(crate) const RGBCONTROLLER_RESIZEZONE: u32 = 1000;
+pub(crate) const RGBCONTROLLER_UPDATELEDS: u32 = 1050;
+pub(crate) const RGBCONTROLLER_UPDATEZONELEDS: u32 = 1051;
+pub(crate) const RGBCONTROLLER_UPDATESINGLELED: u32 = 1052;
+pub(crate) const RGBCONTROLLER_SETCUSTOMMODE: u32 = 1100;
+pub(crate) const RGBCONTROLLER_UPDATEMODE: u32 = 1101;
+pub(crate) const RGBCONTROLLER_SAVEMODE: u32 = 1102;
+
+/// Single packet of the OpenRGB protocol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Packet {
+    pub device_index: u32,
+    pub id: u32,
+    pub data: Vec<u8>,
+}
+
+impl Packet {
+    pub(crate) fn new(device_index: u32, id: u32, data: Vec<u8>) -> Self {
+        Self {
+            device_index,
+            id,
+            data,
+        }
+    }
+}
+
+/// Reads the single packet. Returns `Ok(None)` if the connection is closed before the packet starts
+pub(crate) fn read_packet(reader: &mut impl Read) -> io::Result<Option<Packet>> {
+    let mut header = [0u8; HEADER_SIZE];
+
+    match reader.read_exact(&mut header) {
+        Ok(()) => (),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+
+    if &header[..4] != MAGIC {
+        return Err(invalid_data("Packet doesn't start with the ORGB magic"));
+    }
+
+    let mut header = PacketReader::new(&header[4..]);
+    let device_index = header.u32()?;
+    let id = header.u32()?;
+    let size = header.u32()?;
+
+    if size > MAX_DATA_SIZE {
+        return Err(invalid_data("Packet is too large"));
+    }
+
+    let mut data = vec![0u8; size as usize];
+    reader.read_exact(&mut data)?;
+
+    Ok(Some(Packet {
+        device_index,
+        id,
+        data,
+    }))
+}
+
+/// Writes the single packet
+pub(crate) fn write_packet(writer: &mut impl Write, packet: &Packet) -> io::Result<()> {
+    let size = u32::try_from(packet.data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Packet is too large"))?;
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&packet.device_index.to_le_bytes())?;
+    writer.write_all(&packet.id.to_le_bytes())?;
+    writer.write_all(&size.to_le_bytes())?;
+    writer.write_all(&packet.data)?;
+    writer.flush()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Builder of the packet data. All of the values are little endian
+#[derive(Debug, Default)]
+pub(crate) struct PacketWriter(Vec<u8>);
+
+impl PacketWriter {
+    pub(crate) fn u16(&mut self, value: u16) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(crate) fn i32(&mut self, value: i32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Writes null-terminated string prefixed with its length
+    pub(crate) fn string(&mut self, value: &str) -> &mut Self {
+        // strings in the protocol are limited by u16 length including the null terminator
+        let bytes = &value.as_bytes()[..value.len().min(u16::MAX as usize - 1)];
+
+        self.u16(bytes.len() as u16 + 1);
+        self.0.extend_from_slice(bytes);
+        self.0.push(0);
+        self
+    }
+
+    pub(crate) fn color(&mut self, color: &Color) -> &mut Self {
+        let channel = |value: u32| value.min(u8::MAX as u32) as u8;
+
+        self.0.extend_from_slice(&[
+            channel(color.red),
+            channel(color.green),
+            channel(color.blue),
+            0,
+        ]);
+        self
+    }
+
+    pub(crate) fn bytes(&mut self, value: &[u8]) -> &mut Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub(crate) fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// Parser of the packet data. All of the values are little endian
+#[derive(Debug)]
+pub(crate) struct PacketReader<'a>(&'a [u8]);
+
+impl<'a> PacketReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let bytes = self.slice(N)?;
+
+        Ok(bytes.try_into().expect("slice has the requested length"))
+    }
+
+    fn slice(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid_data("Packet data is truncated"));
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Ok(bytes)
+    }
+
+    pub(crate) fn u16(&mut self) -> io::Result<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    pub(crate) fn u32(&mut self) -> io::Result<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    pub(crate) fn i32(&mut self) -> io::Result<i32> {
+        self.take().map(i32::from_le_bytes)
+    }
+
+    /// Reads null-terminated string prefixed with its length
+    pub(crate) fn string(&mut self) -> io::Result<String> {
+        let len = self.u16()? as usize;
+        let bytes = self.slice(len)?;
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Reads null-terminated string without length that takes the rest of the data
+    pub(crate) fn rest_string(&mut self) -> String {
+        let bytes = std::mem::take(&mut self.0);
+        let bytes = bytes.split(|byte| *byte == 0).next().unwrap_or(bytes);
+
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    pub(crate) fn color(&mut self) -> io::Result<Color> {
+        let [red, green, blue, _] = self.take()?;
+
+        Ok(Color {
+            red: red.into(),
+            green: green.into(),
+            blue: blue.into(),
+        })
+    }
+
+    pub(crate) fn colors(&mut self) -> io::Result<Vec<Color>> {
+        let count = self.u16()?;
+
+        (0..count).map(|_| self.color()).collect()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_roundtrip() {
+        let mut data = PacketWriter::default();
+        data.string("MSI_MB").u16(1).color(&Color {
+            red: 1,
+            green: 2,
+            blue: 3,
+        });
+
+        let packet = Packet::new(2, RGBCONTROLLER_UPDATELEDS, data.into_inner());
+        let mut buffer = Vec::new();
+
+        write_packet(&mut buffer, &packet).unwrap();
+
+        assert_eq!(&buffer[..4], MAGIC);
+
+        let read = read_packet(&mut buffer.as_slice()).unwrap().unwrap();
+        let mut reader = PacketReader::new(&read.data);
+
+        assert_eq!(read, packet);
+        assert_eq!(reader.string().unwrap(), "MSI_MB");
+        assert_eq!(reader.u16().unwrap(), 1);
+        assert_eq!(reader.color().unwrap().blue, 3);
+        assert!(reader.is_empty());
+        assert!(reader.u32().is_err());
+    }
+
+    #[test]
+    fn invalid_magic_is_rejected() {
+        let mut buffer = Vec::new();
+
+        write_packet(
+            &mut buffer,
+            &Packet::new(0, REQUEST_CONTROLLER_COUNT, Vec::new()),
+        )
+        .unwrap();
+        buffer[0] = b'X';
+
+        assert_eq!(
+            read_packet(&mut buffer.as_slice()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
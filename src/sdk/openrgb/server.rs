@@ -0,0 +1,241 @@
+use std::fmt::Debug;
+use std::io::{self, BufReader, BufWriter};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+
+use super::controller::{Controller, ModeUpdate};
+use super::protocol::*;
+use super::{network_error, OPENRGB_PROTOCOL_VERSION};
+use crate::{Device, MysticLightSDK, Result};
+
+/// Server of the [OpenRGB SDK](https://gitlab.com/CalcProgrammer1/OpenRGB/-/wikis/OpenRGB-SDK-Documentation) network protocol
+///
+/// Every device of the sdk is exposed as the OpenRGB controller, controllers are ordered the same way the sdk enumerates devices.
+/// Leds of the device become zones of the controller and supported styles become its modes
+///
+/// # Examples
+///
+/// ```ignore
+/// use mystic_light_sdk::{MysticLightSDK, OpenRgbServer, OPENRGB_DEFAULT_PORT};
+///
+/// let sdk = MysticLightSDK::new("sdk/MysticLight_SDK_x64.dll")?;
+/// let server = OpenRgbServer::bind(sdk, ("127.0.0.1", OPENRGB_DEFAULT_PORT))?;
+///
+/// server.run()?;
+/// ```
+pub struct OpenRgbServer {
+    listener: TcpListener,
+    sdk: Arc<MysticLightSDK>,
+}
+
+impl Debug for OpenRgbServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenRgbServer")
+            .field("listener", &self.listener)
+            .finish()
+    }
+}
+
+impl OpenRgbServer {
+    /// Bind the server to the passed address. Use port `0` to let the os pick free port
+    #[tracing::instrument(level = "debug", skip(sdk, addr))]
+    pub fn bind(sdk: MysticLightSDK, addr: impl ToSocketAddrs) -> Result<Self> {
+        let listener = TcpListener::bind(addr).map_err(network_error)?;
+
+        Ok(Self {
+            listener,
+            sdk: Arc::new(sdk),
+        })
+    }
+
+    /// returns address the server is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(network_error)
+    }
+
+    /// Accept connections and serve them. Blocks the current thread
+    ///
+    /// Failed accepts are logged and skipped, returns error only if the thread for the connection cannot be spawned
+    #[tracing::instrument(level = "debug", skip(self), fields(addr = ?self.listener.local_addr().ok()))]
+    pub fn run(self) -> Result<()> {
+        for stream in self.listener.incoming() {
+            // failed accept (e.g. the client has gone before it was accepted) doesn't affect the other clients
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    tracing::warn!(%error, "Cannot accept OpenRGB client connection");
+
+                    continue;
+                }
+            };
+            let sdk = Arc::clone(&self.sdk);
+
+            thread::Builder::new()
+                .name(String::from("mystic-light-sdk-openrgb"))
+                .spawn(move || {
+                    let peer = stream.peer_addr().ok();
+
+                    tracing::debug!(?peer, "OpenRGB client is connected");
+
+                    match Connection::new(&sdk).serve(stream) {
+                        Ok(()) => tracing::debug!(?peer, "OpenRGB client is disconnected"),
+                        Err(error) => {
+                            tracing::warn!(?peer, %error, "OpenRGB client connection failed")
+                        }
+                    }
+                })
+                .map_err(network_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Connection<'a> {
+    devices: Vec<&'a Device>,
+    /// protocol version negotiated with the client. Clients that don't negotiate use the version 0
+    version: u32,
+}
+
+impl<'a> Connection<'a> {
+    fn new(sdk: &'a MysticLightSDK) -> Self {
+        Self {
+            devices: sdk.devices_iter().collect(),
+            version: 0,
+        }
+    }
+
+    fn serve(mut self, stream: TcpStream) -> io::Result<()> {
+        stream.set_nodelay(true)?;
+
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+
+        while let Some(packet) = read_packet(&mut reader)? {
+            if let Some(response) = self.handle(&packet)? {
+                write_packet(&mut writer, &response)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn controller(&self, device_index: u32) -> Option<Controller<'a>> {
+        let controller = self
+            .devices
+            .get(device_index as usize)
+            .map(|device| Controller::new(device));
+
+        if controller.is_none() {
+            tracing::warn!(device_index, "Controller doesn't exist");
+        }
+
+        controller
+    }
+
+    #[tracing::instrument(level = "trace", skip(self, packet), fields(id = packet.id, device_index = packet.device_index))]
+    fn handle(&mut self, packet: &Packet) -> io::Result<Option<Packet>> {
+        let mut data = PacketReader::new(&packet.data);
+        let response = |data: PacketWriter| {
+            Some(Packet::new(
+                packet.device_index,
+                packet.id,
+                data.into_inner(),
+            ))
+        };
+
+        match packet.id {
+            REQUEST_PROTOCOL_VERSION => {
+                let client_version = if data.is_empty() { 0 } else { data.u32()? };
+                self.version = client_version.min(OPENRGB_PROTOCOL_VERSION);
+
+                let mut data = PacketWriter::default();
+                data.u32(OPENRGB_PROTOCOL_VERSION);
+
+                Ok(response(data))
+            }
+            SET_CLIENT_NAME => {
+                tracing::debug!(name = data.rest_string(), "OpenRGB client name");
+
+                Ok(None)
+            }
+            REQUEST_CONTROLLER_COUNT => {
+                let mut data = PacketWriter::default();
+                data.u32(self.devices.len() as u32);
+
+                Ok(response(data))
+            }
+            REQUEST_CONTROLLER_DATA => {
+                let version = if data.is_empty() {
+                    self.version
+                } else {
+                    data.u32()?.min(OPENRGB_PROTOCOL_VERSION)
+                };
+
+                Ok(self.controller(packet.device_index).map(|controller| {
+                    Packet::new(packet.device_index, packet.id, controller.describe(version))
+                }))
+            }
+            RGBCONTROLLER_UPDATELEDS => {
+                let _data_size = data.u32()?;
+                let colors = data.colors()?;
+
+                if let Some(controller) = self.controller(packet.device_index) {
+                    controller.update_leds(&colors);
+                }
+
+                Ok(None)
+            }
+            RGBCONTROLLER_UPDATEZONELEDS => {
+                let _data_size = data.u32()?;
+                let zone = data.u32()?;
+                let colors = data.colors()?;
+
+                if let Some(controller) = self.controller(packet.device_index) {
+                    controller.update_zone_leds(zone as usize, &colors);
+                }
+
+                Ok(None)
+            }
+            RGBCONTROLLER_UPDATESINGLELED => {
+                let index = data.i32()?;
+                let color = data.color()?;
+
+                if let Some(controller) = self.controller(packet.device_index) {
+                    controller
+                        .update_single_led(usize::try_from(index).unwrap_or(usize::MAX), &color);
+                }
+
+                Ok(None)
+            }
+            // mystic light persists styles by itself so saving the mode is the same as updating it
+            RGBCONTROLLER_UPDATEMODE | RGBCONTROLLER_SAVEMODE => {
+                let update = ModeUpdate::parse(&packet.data, self.version)?;
+
+                if let Some(controller) = self.controller(packet.device_index) {
+                    controller.update_mode(&update);
+                }
+
+                Ok(None)
+            }
+            RGBCONTROLLER_SETCUSTOMMODE => {
+                if let Some(controller) = self.controller(packet.device_index) {
+                    controller.set_custom_mode();
+                }
+
+                Ok(None)
+            }
+            RGBCONTROLLER_RESIZEZONE => {
+                tracing::debug!("Zones of the Mystic Light devices cannot be resized");
+
+                Ok(None)
+            }
+            id => {
+                tracing::debug!(id, "Unsupported OpenRGB packet");
+
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use super::color::Color;
+use super::led::{DeviceLed, DeviceLedState};
+use super::style::{LedStyle, StyleCapabilities};
+use super::types::Result;
+
+/// Capabilities of the led found by trying every supported style on the real hardware
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "async-graphql", derive(async_graphql::SimpleObject))]
+pub struct LedProbeReport {
+    /// name of the probed led
+    pub led: String,
+    /// parameters accepted by the led for every successfully probed style. Parameter is accepted if the write succeeded and the value was read back.
+    /// All of the parameters are `false` if the style itself cannot be applied
+    pub styles: Vec<StyleCapabilities>,
+    /// styles that cannot be probed because the state of the led cannot be read back
+    pub failures: Vec<StyleProbeFailure>,
+}
+
+/// Style which probing has failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "async-graphql", derive(async_graphql::SimpleObject))]
+pub struct StyleProbeFailure {
+    /// style that was probed
+    pub style: LedStyle,
+    /// description of the error
+    pub error: String,
+}
+
+impl DeviceLed {
+    /// Find out which parameters are honoured by every supported style of the led
+    ///
+    /// Every supported style is applied to the led and color, brightness and speed are written and read back.
+    /// Failure of the single style is recorded in the [LedProbeReport::failures] and the rest of the styles are still probed.
+    /// Original state of the led is restored afterwards. Found capabilities override the [LedStyle::capabilities] table for this led,
+    /// see [DeviceLed::apply_probe_report]
+    ///
+    /// Returns error if the original state cannot be read or restored, capabilities are applied to the led in the latter case anyway
+    ///
+    /// # Caveats
+    ///
+    /// The led visibly changes its styles during the probing
+    #[tracing::instrument(level = "debug", skip(self), fields(self.name = self.name()))]
+    pub fn probe(&self) -> Result<LedProbeReport> {
+        self.resolve()?;
+
+        let original = self.get_state()?;
+
+        let mut report = LedProbeReport {
+            led: self.name().to_owned(),
+            styles: Vec::new(),
+            failures: Vec::new(),
+        };
+
+        for style in self.supported_styles() {
+            match self.probe_style(style, &original) {
+                Ok(capabilities) => report.styles.push(capabilities),
+                Err(error) => {
+                    tracing::warn!(%style, %error, "Cannot probe style");
+
+                    report.failures.push(StyleProbeFailure {
+                        style: style.clone(),
+                        error: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        tracing::debug!(?report);
+
+        self.apply_probe_report(&report);
+
+        self.restore_state(&original, &report.styles)?;
+
+        Ok(report)
+    }
+
+    /// Use capabilities from the report e.g. stored after the previous [DeviceLed::probe] instead of the [LedStyle::capabilities] table
+    pub fn apply_probe_report(&self, report: &LedProbeReport) {
+        let mut probed = self.probed_mut();
+
+        for capabilities in &report.styles {
+            probed.insert(capabilities.style.clone(), capabilities.clone());
+        }
+    }
+
+    /// Async version of the [DeviceLed::probe]
+    ///
+    /// Sdk calls are executed on the separate thread, so the returned future doesn't block and may be awaited by any async runtime
+    pub async fn probe_async(&self) -> Result<LedProbeReport> {
+        let led = self.clone();
+
+        self.spawn_blocking(move || led.probe()).await
+    }
+
+    fn probe_style(
+        &self,
+        style: &LedStyle,
+        original: &DeviceLedState,
+    ) -> Result<StyleCapabilities> {
+        let mut capabilities = StyleCapabilities {
+            style: style.clone(),
+            color: false,
+            bright: false,
+            speed: false,
+        };
+
+        if let Err(error) = self.set_style(style) {
+            tracing::debug!(%style, %error, "Cannot apply style");
+
+            return Ok(capabilities);
+        }
+
+        let color = Color {
+            red: 255 - original.color.red.min(255),
+            green: 255 - original.color.green.min(255),
+            blue: 255 - original.color.blue.min(255),
+        };
+
+        if self.set_color(&color).is_ok() {
+            capabilities.color = self.get_state()?.color == color;
+        }
+
+        if self.max_bright() > 0 {
+            let bright = (original.bright + 1) % (self.max_bright() + 1);
+
+            if self.set_bright(bright).is_ok() {
+                capabilities.bright = self.get_state()?.bright == bright;
+            }
+        }
+
+        if self.max_speed() > 0 {
+            let speed = (original.speed + 1) % (self.max_speed() + 1);
+
+            if self.set_speed(speed).is_ok() {
+                capabilities.speed = self.get_state()?.speed == speed;
+            }
+        }
+
+        tracing::debug!(?capabilities);
+
+        Ok(capabilities)
+    }
+
+    /// Restore state of the led. Parameters that were not accepted by the original style during the probing are skipped,
+    /// every parameter is written if the original style wasn't probed successfully. Returns the first error after trying all of the parameters
+    fn restore_state(&self, state: &DeviceLedState, probed: &[StyleCapabilities]) -> Result<()> {
+        let capabilities = probed
+            .iter()
+            .find(|capabilities| capabilities.style == state.style);
+
+        self.set_style(&state.style)?;
+
+        let mut result = Ok(());
+
+        if capabilities.is_none_or(|capabilities| capabilities.color) {
+            result = result.and(self.set_color(&state.color));
+        }
+
+        if capabilities.is_none_or(|capabilities| capabilities.bright) {
+            result = result.and(self.set_bright(state.bright));
+        }
+
+        if capabilities.is_none_or(|capabilities| capabilities.speed) {
+            result = result.and(self.set_speed(state.speed));
+        }
+
+        result
+    }
+}
+
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use crate::sdk::test_support::{find_led, simulated_backend, simulated_sdk};
+    use crate::{LedStyle, MysticLightSDK, SimulatedBackend};
+
+    #[test]
+    fn probes_styles_and_restores_state() {
+        let sdk = simulated_sdk();
+        let led = find_led(&sdk, "JRAINBOW1");
+        let original = led.get_state().unwrap();
+
+        let report = led.probe().unwrap();
+
+        assert_eq!(report.led, "JRAINBOW1");
+        assert_eq!(report.styles.len(), led.supported_styles().len());
+        assert!(report.failures.is_empty());
+
+        let rainbow = report
+            .styles
+            .iter()
+            .find(|capabilities| capabilities.style == LedStyle::Rainbow)
+            .unwrap();
+
+        assert!(!rainbow.color);
+        assert!(rainbow.bright && rainbow.speed);
+        assert!(report.styles[0].color);
+        assert_eq!(led.get_state().unwrap(), original);
+    }
+
+    #[test]
+    fn failed_style_does_not_abort_probing() {
+        let mut fixture = simulated_backend().fixture().clone();
+
+        fixture.devices[0].leds[0].unreadable_styles = vec![String::from("Breathing")];
+
+        let sdk = MysticLightSDK::with_backend(SimulatedBackend::new(fixture)).unwrap();
+        let led = find_led(&sdk, "JRAINBOW1");
+        let original = led.get_state().unwrap();
+
+        let report = led.probe().unwrap();
+
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].style, LedStyle::Breathing);
+        assert_eq!(report.styles.len(), led.supported_styles().len() - 1);
+        assert!(report
+            .styles
+            .iter()
+            .all(|capabilities| capabilities.style != LedStyle::Breathing));
+        assert_eq!(led.get_state().unwrap(), original);
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt::Debug;
+use std::io::{BufReader, BufWriter};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+
+use serde::de::DeserializeOwned;
+
+use super::protocol::{read_frame, write_frame, Request, Response};
+use super::remote_error;
+use crate::backend::{Capabilities, DeviceInfo, LedInfo, LightingBackend};
+use crate::{
+    BrightLevel, Color, CommonError, DeviceIndex, LedIndex, MysticLightSdkResult, Result,
+    SpeedLevel,
+};
+
+/// Backend that forwards every call to the [RemoteServer](crate::RemoteServer) over tcp
+///
+/// Errors of the remote sdk are returned as is, connection and protocol failures are returned as [CommonError::RemoteError]
+///
+/// # Examples
+///
+/// ```ignore
+/// use mystic_light_sdk::{MysticLightSDK, RemoteBackend};
+///
+/// let backend = RemoteBackend::connect("192.168.0.2:6743")?;
+/// let sdk = MysticLightSDK::with_backend(backend)?;
+/// ```
+pub struct RemoteBackend {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+    peer: SocketAddr,
+    capabilities: Capabilities,
+}
+
+impl Debug for RemoteBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteBackend")
+            .field("peer", &self.peer)
+            .field("capabilities", &self.capabilities)
+            .finish()
+    }
+}
+
+impl RemoteBackend {
+    /// Connect to the server and fetch capabilities of the remote sdk
+    #[tracing::instrument(level = "debug", skip(addr))]
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        let stream = TcpStream::connect(addr).map_err(remote_error)?;
+        stream.set_nodelay(true).map_err(remote_error)?;
+
+        let peer = stream.peer_addr().map_err(remote_error)?;
+        let reader = BufReader::new(stream.try_clone().map_err(remote_error)?);
+        let writer = BufWriter::new(stream);
+
+        let mut backend = Self {
+            reader,
+            writer,
+            peer,
+            capabilities: Capabilities::default(),
+        };
+
+        backend.capabilities = backend.call(Request::Capabilities)?;
+
+        Ok(backend)
+    }
+
+    /// returns address of the server
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer
+    }
+
+    fn call<T: DeserializeOwned>(&mut self, request: Request) -> Result<T> {
+        write_frame(&mut self.writer, &request).map_err(remote_error)?;
+
+        let response: Response = read_frame(&mut self.reader)
+            .map_err(remote_error)?
+            .ok_or_else(|| CommonError::RemoteError {
+                message: String::from("Connection is closed by the server"),
+            })?;
+
+        response.decode()
+    }
+}
+
+impl LightingBackend for RemoteBackend {
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.clone()
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        self.call(Request::Initialize)
+    }
+
+    fn get_error_message(&mut self, code: MysticLightSdkResult) -> Result<String> {
+        self.call(Request::GetErrorMessage { code })
+    }
+
+    fn get_device_info(&mut self) -> Result<Vec<DeviceInfo>> {
+        self.call(Request::GetDeviceInfo)
+    }
+
+    fn get_device_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        self.call(Request::GetDeviceName {
+            device_name: device_name.to_owned(),
+        })
+    }
+
+    fn get_device_name_ex(
+        &mut self,
+        device_name: &str,
+        device_index: DeviceIndex,
+    ) -> Result<String> {
+        self.call(Request::GetDeviceNameEx {
+            device_name: device_name.to_owned(),
+            device_index,
+        })
+    }
+
+    fn get_led_name(&mut self, device_name: &str) -> Result<Vec<String>> {
+        self.call(Request::GetLedName {
+            device_name: device_name.to_owned(),
+        })
+    }
+
+    fn get_led_info(&mut self, device_name: &str, led_index: LedIndex) -> Result<LedInfo> {
+        self.call(Request::GetLedInfo {
+            device_name: device_name.to_owned(),
+            led_index,
+        })
+    }
+
+    fn get_led_max_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+    ) -> Result<BrightLevel> {
+        self.call(Request::GetLedMaxBright {
+            device_name: device_name.to_owned(),
+            led_index,
+        })
+    }
+
+    fn get_led_max_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        self.call(Request::GetLedMaxSpeed {
+            device_name: device_name.to_owned(),
+            led_index,
+        })
+    }
+
+    fn get_led_style(&mut self, device_name: &str, led_index: LedIndex) -> Result<String> {
+        self.call(Request::GetLedStyle {
+            device_name: device_name.to_owned(),
+            led_index,
+        })
+    }
+
+    fn get_led_color(&mut self, device_name: &str, led_index: LedIndex) -> Result<Color> {
+        self.call(Request::GetLedColor {
+            device_name: device_name.to_owned(),
+            led_index,
+        })
+    }
+
+    fn get_led_bright(&mut self, device_name: &str, led_index: LedIndex) -> Result<BrightLevel> {
+        self.call(Request::GetLedBright {
+            device_name: device_name.to_owned(),
+            led_index,
+        })
+    }
+
+    fn get_led_speed(&mut self, device_name: &str, led_index: LedIndex) -> Result<SpeedLevel> {
+        self.call(Request::GetLedSpeed {
+            device_name: device_name.to_owned(),
+            led_index,
+        })
+    }
+
+    fn set_led_style(&mut self, device_name: &str, led_index: LedIndex, style: &str) -> Result<()> {
+        self.call(Request::SetLedStyle {
+            device_name: device_name.to_owned(),
+            led_index,
+            style: style.to_owned(),
+        })
+    }
+
+    fn set_led_color(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        color: &Color,
+    ) -> Result<()> {
+        self.call(Request::SetLedColor {
+            device_name: device_name.to_owned(),
+            led_index,
+            color: color.clone(),
+        })
+    }
+
+    fn set_led_colors(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        colors: &[(String, Color)],
+    ) -> Result<()> {
+        self.call(Request::SetLedColors {
+            device_name: device_name.to_owned(),
+            area_index,
+            colors: colors.to_vec(),
+        })
+    }
+
+    fn set_led_color_ex(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        self.call(Request::SetLedColorEx {
+            device_name: device_name.to_owned(),
+            area_index,
+            led_name: led_name.to_owned(),
+            color: color.clone(),
+            force_update,
+        })
+    }
+
+    fn set_led_color_sync(
+        &mut self,
+        device_name: &str,
+        area_index: LedIndex,
+        led_name: &str,
+        color: &Color,
+        force_update: bool,
+    ) -> Result<()> {
+        self.call(Request::SetLedColorSync {
+            device_name: device_name.to_owned(),
+            area_index,
+            led_name: led_name.to_owned(),
+            color: color.clone(),
+            force_update,
+        })
+    }
+
+    fn set_led_bright(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    ) -> Result<()> {
+        self.call(Request::SetLedBright {
+            device_name: device_name.to_owned(),
+            led_index,
+            bright,
+        })
+    }
+
+    fn set_led_speed(
+        &mut self,
+        device_name: &str,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    ) -> Result<()> {
+        self.call(Request::SetLedSpeed {
+            device_name: device_name.to_owned(),
+            led_index,
+            speed,
+        })
+    }
+}
@@ -0,0 +1,116 @@
+//! Remote control of the sdk over the network
+//!
+//! [RemoteServer] wraps [MysticLightSDK](crate::MysticLightSDK) and serves calls of its backend over tcp.
+//! [RemoteBackend] connects to the server and implements [LightingBackend](crate::LightingBackend),
+//! so the [MysticLightSDK](crate::MysticLightSDK) created with it provides the same device and led api as the local one.
+//!
+//! Every message is sent as the frame with the protocol version, payload length and json payload, see [PROTOCOL_VERSION]
+
+mod client;
+mod protocol;
+mod server;
+
+pub use client::*;
+pub use protocol::PROTOCOL_VERSION;
+pub use server::*;
+
+use crate::CommonError;
+
+fn remote_error(error: impl std::fmt::Display) -> CommonError {
+    CommonError::RemoteError {
+        message: error.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::sdk::test_support::{find_led, simulated_backend};
+    use crate::{
+        Capability, Color, DeviceLedState, LedStyle, MysticLightSDK, MysticLightSDKError,
+        SimulatedBackend,
+    };
+
+    fn start_server(backend: SimulatedBackend) -> RemoteBackend {
+        let sdk = MysticLightSDK::with_backend(backend).unwrap();
+        let server = RemoteServer::bind(sdk, "127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+
+        thread::spawn(move || server.run());
+
+        RemoteBackend::connect(addr).unwrap()
+    }
+
+    #[test]
+    fn remote_sdk_controls_leds() {
+        let mut sdk = MysticLightSDK::with_backend(start_server(simulated_backend())).unwrap();
+
+        let names: Vec<_> = sdk.devices_iter().map(|device| device.name()).collect();
+
+        assert_eq!(names, vec!["MSI_MB", "MSI_VGA", "MSI_KEYBOARD"]);
+
+        let led = find_led(&sdk, "JRGB1");
+        let state = DeviceLedState {
+            style: LedStyle::Breathing,
+            color: Color {
+                red: 1,
+                green: 2,
+                blue: 3,
+            },
+            bright: 1,
+            speed: 0,
+        };
+
+        led.set_state(&state).unwrap();
+
+        assert_eq!(led.get_state().unwrap(), state);
+
+        sdk.reload().unwrap();
+
+        assert_eq!(sdk.devices_iter().count(), 3);
+    }
+
+    #[test]
+    fn remote_errors_are_propagated() {
+        let mut fixture = simulated_backend().fixture().clone();
+        fixture
+            .missing_capabilities
+            .push(Capability::SetLedColorSync);
+
+        let sdk =
+            MysticLightSDK::with_backend(start_server(SimulatedBackend::new(fixture))).unwrap();
+
+        assert!(!sdk.capabilities().supports(Capability::SetLedColorSync));
+
+        let led = find_led(&sdk, "VGA");
+
+        let result = led.set_color(&Color {
+            red: 1,
+            green: 2,
+            blue: 3,
+        });
+
+        assert!(matches!(
+            result,
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::Timeout,
+                description: Some(_),
+            })
+        ));
+    }
+
+    #[test]
+    fn closed_server_is_remote_error() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || drop(listener.accept()));
+
+        assert!(matches!(
+            RemoteBackend::connect(addr),
+            Err(CommonError::RemoteError { .. })
+        ));
+    }
+}
@@ -0,0 +1,491 @@
+use std::io::{self, Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    BrightLevel, Capability, Color, CommonError, ConversionError, DeviceIndex, LedIndex,
+    LightingBackend, MysticLightSDK, MysticLightSdkResult, Result, SpeedLevel, SyncError,
+    UsageError,
+};
+
+/// Version of the remote protocol. Server closes connection if the client uses the different version
+///
+/// Frame layout: version (1 byte), payload length (4 bytes, big endian) and json payload
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Max size of the frame payload to protect from the malformed frames
+const MAX_PAYLOAD_LENGTH: u32 = 16 * 1024 * 1024;
+
+/// Call of the backend sent from the client to the server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "call", rename_all = "snake_case")]
+pub(crate) enum Request {
+    Capabilities,
+    Initialize,
+    GetErrorMessage {
+        code: MysticLightSdkResult,
+    },
+    GetDeviceInfo,
+    GetDeviceName {
+        device_name: String,
+    },
+    GetDeviceNameEx {
+        device_name: String,
+        device_index: DeviceIndex,
+    },
+    GetLedName {
+        device_name: String,
+    },
+    GetLedInfo {
+        device_name: String,
+        led_index: LedIndex,
+    },
+    GetLedMaxBright {
+        device_name: String,
+        led_index: LedIndex,
+    },
+    GetLedMaxSpeed {
+        device_name: String,
+        led_index: LedIndex,
+    },
+    GetLedStyle {
+        device_name: String,
+        led_index: LedIndex,
+    },
+    GetLedColor {
+        device_name: String,
+        led_index: LedIndex,
+    },
+    GetLedBright {
+        device_name: String,
+        led_index: LedIndex,
+    },
+    GetLedSpeed {
+        device_name: String,
+        led_index: LedIndex,
+    },
+    SetLedStyle {
+        device_name: String,
+        led_index: LedIndex,
+        style: String,
+    },
+    SetLedColor {
+        device_name: String,
+        led_index: LedIndex,
+        color: Color,
+    },
+    SetLedColors {
+        device_name: String,
+        area_index: LedIndex,
+        colors: Vec<(String, Color)>,
+    },
+    SetLedColorEx {
+        device_name: String,
+        area_index: LedIndex,
+        led_name: String,
+        color: Color,
+        force_update: bool,
+    },
+    SetLedColorSync {
+        device_name: String,
+        area_index: LedIndex,
+        led_name: String,
+        color: Color,
+        force_update: bool,
+    },
+    SetLedBright {
+        device_name: String,
+        led_index: LedIndex,
+        bright: BrightLevel,
+    },
+    SetLedSpeed {
+        device_name: String,
+        led_index: LedIndex,
+        speed: SpeedLevel,
+    },
+}
+
+impl Request {
+    /// Executes the call with the backend and encodes the result
+    pub(crate) fn execute(self, backend: &mut dyn LightingBackend) -> Response {
+        let result = match self {
+            Request::Capabilities => encode(Ok(backend.capabilities())),
+            // backend is initialized once the server's sdk is created and is shared by all of the clients,
+            // so the client gets the result of that initialization instead of re-initializing the sdk
+            Request::Initialize => encode(Ok(())),
+            Request::GetErrorMessage { code } => encode(backend.get_error_message(code)),
+            Request::GetDeviceInfo => encode(backend.get_device_info()),
+            Request::GetDeviceName { device_name } => encode(backend.get_device_name(&device_name)),
+            Request::GetDeviceNameEx {
+                device_name,
+                device_index,
+            } => encode(backend.get_device_name_ex(&device_name, device_index)),
+            Request::GetLedName { device_name } => encode(backend.get_led_name(&device_name)),
+            Request::GetLedInfo {
+                device_name,
+                led_index,
+            } => encode(backend.get_led_info(&device_name, led_index)),
+            Request::GetLedMaxBright {
+                device_name,
+                led_index,
+            } => encode(backend.get_led_max_bright(&device_name, led_index)),
+            Request::GetLedMaxSpeed {
+                device_name,
+                led_index,
+            } => encode(backend.get_led_max_speed(&device_name, led_index)),
+            Request::GetLedStyle {
+                device_name,
+                led_index,
+            } => encode(backend.get_led_style(&device_name, led_index)),
+            Request::GetLedColor {
+                device_name,
+                led_index,
+            } => encode(backend.get_led_color(&device_name, led_index)),
+            Request::GetLedBright {
+                device_name,
+                led_index,
+            } => encode(backend.get_led_bright(&device_name, led_index)),
+            Request::GetLedSpeed {
+                device_name,
+                led_index,
+            } => encode(backend.get_led_speed(&device_name, led_index)),
+            Request::SetLedStyle {
+                device_name,
+                led_index,
+                style,
+            } => encode(backend.set_led_style(&device_name, led_index, &style)),
+            Request::SetLedColor {
+                device_name,
+                led_index,
+                color,
+            } => encode(backend.set_led_color(&device_name, led_index, &color)),
+            Request::SetLedColors {
+                device_name,
+                area_index,
+                colors,
+            } => encode(backend.set_led_colors(&device_name, area_index, &colors)),
+            Request::SetLedColorEx {
+                device_name,
+                area_index,
+                led_name,
+                color,
+                force_update,
+            } => encode(backend.set_led_color_ex(
+                &device_name,
+                area_index,
+                &led_name,
+                &color,
+                force_update,
+            )),
+            Request::SetLedColorSync {
+                device_name,
+                area_index,
+                led_name,
+                color,
+                force_update,
+            } => encode(backend.set_led_color_sync(
+                &device_name,
+                area_index,
+                &led_name,
+                &color,
+                force_update,
+            )),
+            Request::SetLedBright {
+                device_name,
+                led_index,
+                bright,
+            } => encode(backend.set_led_bright(&device_name, led_index, bright)),
+            Request::SetLedSpeed {
+                device_name,
+                led_index,
+                speed,
+            } => encode(backend.set_led_speed(&device_name, led_index, speed)),
+        };
+
+        match result {
+            Ok(value) => Response::Ok(value),
+            Err(error) => Response::Err(WireError::from(&error)),
+        }
+    }
+}
+
+fn encode<T: Serialize>(result: Result<T>) -> Result<Value> {
+    let value = result?;
+
+    serde_json::to_value(value).map_err(|error| CommonError::RemoteError {
+        message: error.to_string(),
+    })
+}
+
+/// Result of the call sent from the server to the client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Response {
+    Ok(Value),
+    Err(WireError),
+}
+
+impl Response {
+    pub(crate) fn decode<T: DeserializeOwned>(self) -> Result<T> {
+        match self {
+            Response::Ok(value) => {
+                serde_json::from_value(value).map_err(|error| CommonError::RemoteError {
+                    message: format!("Invalid response: {error}"),
+                })
+            }
+            Response::Err(error) => Err(error.into()),
+        }
+    }
+}
+
+/// Serializable representation of the [CommonError]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum WireError {
+    Sdk {
+        code: MysticLightSdkResult,
+        description: Option<String>,
+    },
+    NotSupportedCapability {
+        capability: Capability,
+    },
+    MissingExports {
+        exports: Vec<String>,
+    },
+    NotRecorded {
+        call: String,
+    },
+    Conversion {
+        error: ConversionError,
+    },
+    Usage {
+        error: UsageError,
+    },
+    Sync {
+        error: SyncError,
+    },
+    /// dll loading error of the server. It cannot be recreated on the client, so it is received as [CommonError::RemoteError]
+    Library {
+        message: String,
+    },
+    /// connection or protocol failure
+    Remote {
+        message: String,
+    },
+}
+
+impl From<&CommonError> for WireError {
+    fn from(error: &CommonError) -> Self {
+        match error {
+            CommonError::SdkError {
+                source,
+                description,
+            } => WireError::Sdk {
+                code: source.code(),
+                description: description.clone(),
+            },
+            CommonError::NotSupportedCapability { capability } => {
+                WireError::NotSupportedCapability {
+                    capability: *capability,
+                }
+            }
+            CommonError::MissingExports { exports } => WireError::MissingExports {
+                exports: exports.clone(),
+            },
+            CommonError::NotRecorded { call } => WireError::NotRecorded { call: call.clone() },
+            CommonError::ConversionError { source } => WireError::Conversion {
+                error: source.clone(),
+            },
+            CommonError::UsageError { source } => WireError::Usage {
+                error: source.clone(),
+            },
+            CommonError::SyncError { source } => WireError::Sync {
+                error: source.clone(),
+            },
+            CommonError::LibraryError { source } => WireError::Library {
+                message: source.to_string(),
+            },
+            CommonError::RemoteError { message } => WireError::Remote {
+                message: message.clone(),
+            },
+        }
+    }
+}
+
+impl From<WireError> for CommonError {
+    fn from(error: WireError) -> Self {
+        match error {
+            WireError::Sdk { code, description } => match MysticLightSDK::parse_result(code) {
+                Ok(()) => CommonError::RemoteError {
+                    message: String::from("Server returned error with the success code"),
+                },
+                Err(source) => CommonError::SdkError {
+                    source,
+                    description,
+                },
+            },
+            WireError::NotSupportedCapability { capability } => {
+                CommonError::NotSupportedCapability { capability }
+            }
+            WireError::MissingExports { exports } => CommonError::MissingExports { exports },
+            WireError::NotRecorded { call } => CommonError::NotRecorded { call },
+            WireError::Conversion { error } => CommonError::ConversionError { source: error },
+            WireError::Usage { error } => CommonError::UsageError { source: error },
+            WireError::Sync { error } => CommonError::SyncError { source: error },
+            WireError::Library { message } => CommonError::RemoteError {
+                message: format!("LibraryError({message})"),
+            },
+            WireError::Remote { message } => CommonError::RemoteError { message },
+        }
+    }
+}
+
+/// Writes the message as the single frame
+pub(crate) fn write_frame<T: Serialize>(writer: &mut impl Write, message: &T) -> io::Result<()> {
+    let payload = serde_json::to_vec(message)?;
+    let length = u32::try_from(payload.len())
+        .ok()
+        .filter(|length| *length <= MAX_PAYLOAD_LENGTH)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Frame is too large"))?;
+
+    writer.write_all(&[PROTOCOL_VERSION])?;
+    writer.write_all(&length.to_be_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()
+}
+
+/// Reads the single frame. Returns `Ok(None)` if the connection is closed before the frame starts
+pub(crate) fn read_frame<T: DeserializeOwned>(reader: &mut impl Read) -> io::Result<Option<T>> {
+    let mut version = [0u8; 1];
+
+    match reader.read_exact(&mut version) {
+        Ok(()) => (),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+
+    if version[0] != PROTOCOL_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Unsupported protocol version {}, expected {PROTOCOL_VERSION}",
+                version[0]
+            ),
+        ));
+    }
+
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length);
+
+    if length > MAX_PAYLOAD_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Frame is too large",
+        ));
+    }
+
+    let mut payload = vec![0u8; length as usize];
+    reader.read_exact(&mut payload)?;
+
+    Ok(Some(serde_json::from_slice(&payload)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MysticLightSDKError;
+
+    #[test]
+    fn frame_roundtrip() {
+        let request = Request::SetLedStyle {
+            device_name: String::from("MSI_MB"),
+            led_index: 1,
+            style: String::from("Breathing"),
+        };
+        let mut buffer = Vec::new();
+
+        write_frame(&mut buffer, &request).unwrap();
+
+        assert_eq!(buffer[0], PROTOCOL_VERSION);
+        assert_eq!(
+            read_frame::<Request>(&mut buffer.as_slice()).unwrap(),
+            Some(request)
+        );
+        assert_eq!(read_frame::<Request>(&mut [].as_slice()).unwrap(), None);
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        let mut buffer = Vec::new();
+
+        write_frame(&mut buffer, &Request::GetDeviceInfo).unwrap();
+        buffer[0] = PROTOCOL_VERSION + 1;
+
+        assert_eq!(
+            read_frame::<Request>(&mut buffer.as_slice())
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn errors_are_transferred() {
+        let error = CommonError::SdkError {
+            source: MysticLightSDKError::Timeout,
+            description: Some(String::from("Request is timeout")),
+        };
+
+        let transferred: CommonError = WireError::from(&error).into();
+
+        assert!(matches!(
+            transferred,
+            CommonError::SdkError {
+                source: MysticLightSDKError::Timeout,
+                description: Some(_),
+            }
+        ));
+    }
+
+    #[test]
+    fn usage_errors_are_transferred() {
+        let error = CommonError::UsageError {
+            source: UsageError::ExcessBrightLevel {
+                level: 11,
+                max_level: 10,
+            },
+        };
+
+        let response = serde_json::to_string(&WireError::from(&error)).unwrap();
+        let transferred: CommonError = serde_json::from_str::<WireError>(&response).unwrap().into();
+
+        assert!(matches!(
+            transferred,
+            CommonError::UsageError {
+                source: UsageError::ExcessBrightLevel {
+                    level: 11,
+                    max_level: 10
+                }
+            }
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn initialize_is_not_forwarded_to_the_backend() {
+        use crate::{SimulatedBackend, SimulatedFixture};
+
+        let mut backend = SimulatedBackend::new(SimulatedFixture {
+            initialize_result: -2,
+            ..Default::default()
+        });
+
+        assert!(Request::Initialize
+            .execute(&mut backend)
+            .decode::<()>()
+            .is_ok());
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::Debug;
+use std::io::{BufReader, BufWriter};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread;
+
+use super::protocol::{read_frame, write_frame, Request, Response, WireError};
+use super::remote_error;
+use crate::backend::SharedBackend;
+use crate::{MysticLightSDK, Result};
+
+/// Tcp server that executes the calls of the connected [RemoteBackend](crate::RemoteBackend) clients with the local sdk
+///
+/// Every connection is served by its own thread, calls of the different clients are serialized by the sdk backend
+///
+/// # Examples
+///
+/// ```ignore
+/// use mystic_light_sdk::{MysticLightSDK, RemoteServer};
+///
+/// let sdk = MysticLightSDK::new("sdk/MysticLight_SDK_x64.dll")?;
+/// let server = RemoteServer::bind(sdk, "127.0.0.1:6743")?;
+///
+/// server.run()?;
+/// ```
+pub struct RemoteServer {
+    listener: TcpListener,
+    backend: SharedBackend,
+    // keeps the sdk alive while the server is running
+    _sdk: MysticLightSDK,
+}
+
+impl Debug for RemoteServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteServer")
+            .field("listener", &self.listener)
+            .finish()
+    }
+}
+
+impl RemoteServer {
+    /// Bind the server to the passed address. Use port `0` to let the os pick free port
+    #[tracing::instrument(level = "debug", skip(sdk, addr))]
+    pub fn bind(sdk: MysticLightSDK, addr: impl ToSocketAddrs) -> Result<Self> {
+        let listener = TcpListener::bind(addr).map_err(remote_error)?;
+        let backend = sdk.shared_backend().clone();
+
+        Ok(Self {
+            listener,
+            backend,
+            _sdk: sdk,
+        })
+    }
+
+    /// returns address the server is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener.local_addr().map_err(remote_error)
+    }
+
+    /// Accept connections and serve them. Blocks the current thread
+    ///
+    /// Failed accepts are logged and skipped, returns error only if the thread for the connection cannot be spawned
+    #[tracing::instrument(level = "debug", skip(self), fields(addr = ?self.listener.local_addr().ok()))]
+    pub fn run(self) -> Result<()> {
+        for stream in self.listener.incoming() {
+            // failed accept (e.g. the client has gone before it was accepted) doesn't affect the other clients
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    tracing::warn!(%error, "Cannot accept client connection");
+
+                    continue;
+                }
+            };
+            let backend = self.backend.clone();
+
+            thread::Builder::new()
+                .name(String::from("mystic-light-sdk-remote"))
+                .spawn(move || {
+                    let peer = stream.peer_addr().ok();
+
+                    tracing::debug!(?peer, "Client is connected");
+
+                    match serve_connection(stream, &backend) {
+                        Ok(()) => tracing::debug!(?peer, "Client is disconnected"),
+                        Err(error) => tracing::warn!(?peer, %error, "Client connection failed"),
+                    }
+                })
+                .map_err(remote_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn serve_connection(stream: TcpStream, backend: &SharedBackend) -> Result<()> {
+    stream.set_nodelay(true).map_err(remote_error)?;
+
+    let mut reader = BufReader::new(stream.try_clone().map_err(remote_error)?);
+    let mut writer = BufWriter::new(stream);
+
+    loop {
+        let request: Request = match read_frame(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(error) => {
+                // e.g. unsupported protocol version, let the client know why the connection is closed
+                let response = Response::Err(WireError::Remote {
+                    message: error.to_string(),
+                });
+                let _ = write_frame(&mut writer, &response);
+
+                return Err(remote_error(error));
+            }
+        };
+
+        tracing::trace!(?request, "Remote call");
+
+        let response = match backend.lock() {
+            Ok(mut backend) => request.execute(&mut *backend),
+            Err(error) => Response::Err(WireError::from(&error.into())),
+        };
+
+        write_frame(&mut writer, &response).map_err(remote_error)?;
+    }
+}
@@ -0,0 +1,426 @@
+#[cfg(feature = "async-graphql")]
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+macro_rules! led_styles {
+    ($($variant:ident),* $(,)?) => {
+        /// Style (effect) of the led
+        ///
+        /// Covers styles documented by the sdk. Styles that are unknown to this crate are kept as is in the [LedStyle::Other]
+        #[derive(Debug, Clone)]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(from = "String", into = "String")
+        )]
+        #[non_exhaustive]
+        pub enum LedStyle {
+            $($variant,)*
+            /// style that is not documented by the sdk
+            Other(String),
+        }
+
+        /// Style documented by the sdk, see [LedStyle]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "async-graphql", derive(async_graphql::Enum))]
+        #[non_exhaustive]
+        pub enum KnownLedStyle {
+            $($variant,)*
+        }
+
+        impl LedStyle {
+            /// all of the documented styles
+            pub const KNOWN: &'static [LedStyle] = &[$(LedStyle::$variant),*];
+
+            /// returns name of the style as it is used by the sdk
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(LedStyle::$variant => stringify!($variant),)*
+                    LedStyle::Other(name) => name,
+                }
+            }
+
+            /// returns documented style or `None` if the style is unknown
+            pub fn known(&self) -> Option<KnownLedStyle> {
+                match self.as_str() {
+                    $(stringify!($variant) => Some(KnownLedStyle::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<KnownLedStyle> for LedStyle {
+            fn from(style: KnownLedStyle) -> Self {
+                match style {
+                    $(KnownLedStyle::$variant => LedStyle::$variant,)*
+                }
+            }
+        }
+
+        impl From<&str> for LedStyle {
+            fn from(name: &str) -> Self {
+                match name {
+                    $(stringify!($variant) => LedStyle::$variant,)*
+                    name => LedStyle::Other(name.to_owned()),
+                }
+            }
+        }
+    };
+}
+
+led_styles! {
+    NoAnimation,
+    Breathing,
+    Flashing,
+    DoubleFlashing,
+    Lightning,
+    MSIMarquee,
+    Meteor,
+    WaterDrop,
+    MSIRainbow,
+    Pop,
+    Rap,
+    Jazz,
+    Play,
+    Movie,
+    ColorRing,
+    Planetary,
+    DoubleMeteor,
+    Energy,
+    Blink,
+    Clock,
+    ColorPulse,
+    ColorShift,
+    ColorWave,
+    Marquee,
+    Rainbow,
+    RainbowWave,
+    Visor,
+    JRainbow,
+    RainbowFlashing,
+    RainbowDoubleFlashing,
+    Random,
+}
+
+impl LedStyle {
+    /// returns whether the style is documented by the sdk
+    pub fn is_known(&self) -> bool {
+        self.known().is_some()
+    }
+
+    /// returns parameters of the led state that the style is expected to honour regardless of the led limits or `None` if the style is unknown.
+    ///
+    /// The table is advisory only: it is not verified on every firmware and writes are never filtered by it.
+    /// Use [DeviceLed::probe](crate::DeviceLed::probe) to find out the actual behaviour of the led
+    pub fn capabilities(&self) -> Option<StyleCapabilities> {
+        let (color, bright, speed) = match self {
+            LedStyle::NoAnimation | LedStyle::Clock => (true, true, false),
+            LedStyle::Breathing
+            | LedStyle::Flashing
+            | LedStyle::DoubleFlashing
+            | LedStyle::Lightning
+            | LedStyle::MSIMarquee
+            | LedStyle::Meteor
+            | LedStyle::WaterDrop
+            | LedStyle::DoubleMeteor
+            | LedStyle::Energy
+            | LedStyle::Blink
+            | LedStyle::Marquee
+            | LedStyle::Visor => (true, true, true),
+            LedStyle::MSIRainbow
+            | LedStyle::ColorRing
+            | LedStyle::Planetary
+            | LedStyle::ColorPulse
+            | LedStyle::ColorShift
+            | LedStyle::ColorWave
+            | LedStyle::Rainbow
+            | LedStyle::RainbowWave
+            | LedStyle::JRainbow
+            | LedStyle::RainbowFlashing
+            | LedStyle::RainbowDoubleFlashing
+            | LedStyle::Random => (false, true, true),
+            // music styles follow the sound instead of the speed
+            LedStyle::Pop | LedStyle::Rap | LedStyle::Jazz | LedStyle::Play | LedStyle::Movie => {
+                (false, true, false)
+            }
+            LedStyle::Other(_) => return None,
+        };
+
+        Some(StyleCapabilities {
+            style: self.clone(),
+            color,
+            bright,
+            speed,
+        })
+    }
+}
+
+/// Parameters of the led state that are honoured by the style. Values of the other parameters are expected to be ignored by the sdk
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "async-graphql", derive(async_graphql::SimpleObject))]
+pub struct StyleCapabilities {
+    /// style these capabilities belong to
+    pub style: LedStyle,
+    /// whether the style uses color of the led
+    pub color: bool,
+    /// whether the style uses brightness of the led
+    pub bright: bool,
+    /// whether the style uses speed of the led
+    pub speed: bool,
+}
+
+impl From<String> for LedStyle {
+    fn from(name: String) -> Self {
+        match LedStyle::from(name.as_str()) {
+            LedStyle::Other(_) => LedStyle::Other(name),
+            style => style,
+        }
+    }
+}
+
+impl From<LedStyle> for String {
+    fn from(style: LedStyle) -> Self {
+        match style {
+            LedStyle::Other(name) => name,
+            style => style.as_str().to_owned(),
+        }
+    }
+}
+
+impl FromStr for LedStyle {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(LedStyle::from(name))
+    }
+}
+
+impl Display for LedStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// styles are compared by name, so `LedStyle::Other("Breathing")` is the same as `LedStyle::Breathing`
+impl PartialEq for LedStyle {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for LedStyle {}
+
+impl Hash for LedStyle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl PartialEq<str> for LedStyle {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for LedStyle {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Style of the led. `known` is null for the styles that are not documented by the sdk
+#[cfg(feature = "async-graphql")]
+#[async_graphql::Object(name = "LedStyle")]
+impl LedStyle {
+    /// name of the style as it is used by the sdk
+    #[graphql(name = "name")]
+    async fn async_graphql_name(&self) -> &str {
+        self.as_str()
+    }
+
+    #[graphql(name = "known")]
+    async fn async_graphql_known(&self) -> Option<KnownLedStyle> {
+        self.known()
+    }
+}
+
+/// Style of the led. Documented styles are passed as `known` and any other style name as `other`
+#[cfg(feature = "async-graphql")]
+#[derive(async_graphql::OneofObject)]
+enum LedStyleInput {
+    /// style documented by the sdk
+    Known(KnownLedStyle),
+    /// name of the style that is not documented by the sdk
+    Other(String),
+}
+
+#[cfg(feature = "async-graphql")]
+impl From<LedStyleInput> for LedStyle {
+    fn from(style: LedStyleInput) -> Self {
+        match style {
+            LedStyleInput::Known(style) => LedStyle::from(style),
+            LedStyleInput::Other(name) => LedStyle::from(name),
+        }
+    }
+}
+
+// input is the `LedStyleInput` oneof object, so the output object and the input don't clash by name
+#[cfg(feature = "async-graphql")]
+impl async_graphql::InputType for LedStyle {
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> {
+        <LedStyleInput as async_graphql::InputType>::type_name()
+    }
+
+    fn create_type_info(registry: &mut async_graphql::registry::Registry) -> String {
+        <LedStyleInput as async_graphql::InputType>::create_type_info(registry)
+    }
+
+    fn parse(value: Option<async_graphql::Value>) -> async_graphql::InputValueResult<Self> {
+        <LedStyleInput as async_graphql::InputType>::parse(value)
+            .map(LedStyle::from)
+            .map_err(async_graphql::InputValueError::propagate)
+    }
+
+    fn to_value(&self) -> async_graphql::Value {
+        let input = match self.known() {
+            Some(style) => LedStyleInput::Known(style),
+            None => LedStyleInput::Other(self.as_str().to_owned()),
+        };
+
+        async_graphql::InputType::to_value(&input)
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_and_unknown_styles() {
+        assert_eq!(LedStyle::from("Breathing"), LedStyle::Breathing);
+        assert_eq!(
+            LedStyle::from("CustomEffect"),
+            LedStyle::Other(String::from("CustomEffect"))
+        );
+        assert!(LedStyle::MSIRainbow.is_known());
+        assert!(!LedStyle::from("CustomEffect").is_known());
+        assert_eq!(
+            LedStyle::Other(String::from("Rainbow")).known(),
+            Some(KnownLedStyle::Rainbow)
+        );
+        assert_eq!(
+            LedStyle::from(KnownLedStyle::ColorWave),
+            LedStyle::ColorWave
+        );
+    }
+
+    #[cfg(feature = "async-graphql")]
+    #[test]
+    fn parses_graphql_input() {
+        use async_graphql::{value, InputType};
+
+        assert_eq!(
+            LedStyle::parse(Some(value!({ "known": "MSI_RAINBOW" }))).unwrap(),
+            LedStyle::MSIRainbow
+        );
+        assert_eq!(
+            LedStyle::parse(Some(value!({ "other": "CustomEffect" }))).unwrap(),
+            LedStyle::from("CustomEffect")
+        );
+        assert!(LedStyle::parse(Some(value!("Breathing"))).is_err());
+        assert_eq!(
+            InputType::to_value(&LedStyle::Breathing),
+            value!({ "known": "BREATHING" })
+        );
+    }
+
+    #[test]
+    fn styles_define_honoured_parameters() {
+        let capabilities = LedStyle::NoAnimation.capabilities().unwrap();
+
+        assert!(capabilities.color && capabilities.bright && !capabilities.speed);
+        assert!(!LedStyle::Rainbow.capabilities().unwrap().color);
+        assert!(!LedStyle::Jazz.capabilities().unwrap().speed);
+        assert_eq!(LedStyle::from("CustomEffect").capabilities(), None);
+    }
+
+    #[test]
+    fn styles_are_compared_by_name() {
+        assert_eq!(LedStyle::Other(String::from("Rainbow")), LedStyle::Rainbow);
+        assert_eq!(LedStyle::ColorWave, "ColorWave");
+        assert_eq!(LedStyle::ColorWave.to_string(), "ColorWave");
+        assert!(LedStyle::KNOWN.iter().all(LedStyle::is_known));
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn style_capabilities_are_advisory() {
+        use crate::sdk::test_support::{find_led, simulated_sdk};
+        use crate::{Color, CommonError, DeviceLedState, DeviceLedStateInput, MysticLightSDKError};
+
+        let sdk = simulated_sdk();
+        let led = find_led(&sdk, "JRAINBOW1");
+
+        let capabilities = led.supported_style_capabilities();
+
+        assert_eq!(capabilities.len(), led.supported_styles().len());
+        assert!(!led.style_capabilities(&LedStyle::Rainbow).unwrap().color);
+        assert!(
+            !led.style_capabilities(&LedStyle::NoAnimation)
+                .unwrap()
+                .speed
+        );
+        assert!(led.style_capabilities(&LedStyle::MSIRainbow).is_none());
+
+        let state = DeviceLedState {
+            style: LedStyle::NoAnimation,
+            color: Color {
+                red: 1,
+                green: 2,
+                blue: 3,
+            },
+            bright: 1,
+            speed: 0,
+        };
+
+        led.set_state(&state).unwrap();
+
+        // speed is expected to be ignored by the style, but it is still sent to the sdk
+        assert_eq!(led.get_state().unwrap(), state);
+
+        let result = led.merge_with_state(&DeviceLedStateInput {
+            style: Some(LedStyle::Rainbow),
+            color: Some(state.color.clone()),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            result,
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::Timeout,
+                ..
+            })
+        ));
+
+        led.probe().unwrap();
+
+        // simulated led keeps the speed of the static style, so the probe overrides the table
+        assert!(
+            led.style_capabilities(&LedStyle::NoAnimation)
+                .unwrap()
+                .speed
+        );
+        assert!(!led.style_capabilities(&LedStyle::Rainbow).unwrap().color);
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Debug;
+
+use super::color::Color;
+use super::led::DeviceLed;
+use super::types::Result;
+
+/// Defines how the color is written to the sub-led
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubLedWriteMode {
+    /// Uses `MLAPI_SetLedColorEx`. Call returns as soon as the sdk accepts the request, so the color may be applied by the hardware later
+    #[default]
+    Async,
+    /// Uses `MLAPI_SetLedColorSync`. Call returns only after the color is applied by the hardware
+    Sync,
+}
+
+/// Single individually addressable led inside the led area of the device e.g. key of the keyboard or pixel of the led strip
+///
+/// Sub-led is addressed by the led area it belongs to and its name
+pub struct SubLed<'a> {
+    area: &'a DeviceLed,
+    name: String,
+}
+
+impl<'a> Debug for SubLed<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SubLed")
+            .field("area", &self.area.name())
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl<'a> SubLed<'a> {
+    pub(crate) fn new(area: &'a DeviceLed, name: String) -> Self {
+        Self { area, name }
+    }
+
+    /// returns name of the sub-led
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// returns led area the sub-led belongs to
+    pub fn area(&self) -> &DeviceLed {
+        self.area
+    }
+
+    /// Set color of the sub-led
+    ///
+    /// `force_update` is passed as the trailing flag of the underlying sdk call
+    pub fn set_color(
+        &self,
+        color: &Color,
+        mode: SubLedWriteMode,
+        force_update: bool,
+    ) -> Result<()> {
+        self.area
+            .set_sub_led_color(&self.name, color, mode, force_update)
+    }
+}
+
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use super::*;
+    use crate::sdk::test_support::{find_led, simulated_sdk};
+    use crate::{CommonError, MysticLightSDKError};
+
+    #[test]
+    fn set_sub_led_color() {
+        let sdk = simulated_sdk();
+        let led = find_led(&sdk, "Keyboard");
+        let color = Color {
+            red: 0,
+            green: 255,
+            blue: 0,
+        };
+
+        led.sub_led("Esc")
+            .set_color(&color, SubLedWriteMode::Async, true)
+            .unwrap();
+        led.sub_led("W")
+            .set_color(&color, SubLedWriteMode::Sync, true)
+            .unwrap();
+
+        assert!(matches!(
+            led.sub_led("F13")
+                .set_color(&color, SubLedWriteMode::Sync, true),
+            Err(CommonError::SdkError {
+                source: MysticLightSDKError::InvalidArgument,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use super::error::{CommonError, SyncError};
+use super::types::Result;
+
+const BLOCKING_THREAD_NAME: &str = "mystic-light-sdk-async";
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct ReplyState<T> {
+    value: Option<Result<T>>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+/// Sending half of the [Reply]. Reply resolves with `SyncError::Disconnected` if the sender is dropped without sending the value
+pub(crate) struct ReplySender<T>(Arc<Mutex<ReplyState<T>>>);
+
+/// Future that resolves with the value sent by the [ReplySender]. Doesn't depend on any async runtime
+pub(crate) struct Reply<T>(Arc<Mutex<ReplyState<T>>>);
+
+/// Creates single-use channel to pass the result of the blocking call to the async code
+pub(crate) fn reply_channel<T>() -> (ReplySender<T>, Reply<T>) {
+    let state = Arc::new(Mutex::new(ReplyState {
+        value: None,
+        waker: None,
+        closed: false,
+    }));
+
+    (ReplySender(Arc::clone(&state)), Reply(state))
+}
+
+impl<T> ReplySender<T> {
+    pub(crate) fn send(self, value: Result<T>) {
+        if let Ok(mut state) = self.0.lock() {
+            state.value = Some(value);
+        }
+    }
+}
+
+impl<T> Drop for ReplySender<T> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.0.lock() {
+            state.closed = true;
+
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> Future for Reply<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = match self.0.lock() {
+            Ok(state) => state,
+            Err(error) => return Poll::Ready(Err(error.into())),
+        };
+
+        if let Some(value) = state.value.take() {
+            return Poll::Ready(value);
+        }
+
+        if state.closed {
+            return Poll::Ready(Err(CommonError::from(SyncError::Disconnected)));
+        }
+
+        state.waker = Some(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+/// Dedicated thread that executes blocking sdk calls for the async code
+///
+/// Every backend has its own worker, so the async calls to the different backends don't wait for each other.
+/// Thread is spawned on the first call and stops after the worker is dropped and the submitted calls are executed
+#[derive(Default)]
+pub(crate) struct BlockingWorker {
+    sender: OnceLock<Sender<Job>>,
+}
+
+impl BlockingWorker {
+    fn sender(&self) -> &Sender<Job> {
+        self.sender.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel::<Job>();
+
+            let spawned = thread::Builder::new()
+                .name(BLOCKING_THREAD_NAME.to_owned())
+                .spawn(move || {
+                    while let Ok(job) = receiver.recv() {
+                        // panic inside the job drops its reply sender, so the caller gets `SyncError::Disconnected`
+                        let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                    }
+                });
+
+            if let Err(error) = spawned {
+                tracing::error!(%error, "Cannot spawn thread for the async sdk calls");
+            }
+
+            sender
+        })
+    }
+
+    /// Runs blocking sdk call on the worker thread and returns future with its result
+    ///
+    /// Calls are executed one by one in the order they were submitted as the sdk doesn't support parallel access anyway
+    pub(crate) fn spawn_blocking<T, F>(&self, f: F) -> Reply<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T> + Send + 'static,
+    {
+        let (reply_sender, reply) = reply_channel();
+
+        // if the worker is not available the job is dropped with the reply sender and the reply resolves with an error
+        let _ = self.sender().send(Box::new(move || reply_sender.send(f())));
+
+        reply
+    }
+}
+
+/// Runs blocking call that doesn't belong to any backend yet e.g. backend initialization on the separate one-off thread
+pub(crate) fn spawn_blocking<T, F>(f: F) -> Reply<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    // worker thread stops right after the call as the worker is dropped here
+    BlockingWorker::default().spawn_blocking(f)
+}
+
+/// Minimal executor to drive futures in tests
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    use std::task::Wake;
+
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawn_blocking_returns_result() {
+        let result = block_on(spawn_blocking(|| {
+            Ok(thread::current().name().map(String::from))
+        }));
+
+        assert_eq!(result.unwrap().as_deref(), Some(BLOCKING_THREAD_NAME));
+    }
+
+    #[test]
+    fn panic_resolves_with_error() {
+        let worker = BlockingWorker::default();
+        let result = block_on(worker.spawn_blocking(|| -> Result<()> { panic!("call failed") }));
+
+        assert!(matches!(
+            result,
+            Err(CommonError::SyncError {
+                source: SyncError::Disconnected
+            })
+        ));
+        assert!(block_on(worker.spawn_blocking(|| Ok(()))).is_ok());
+    }
+
+    #[test]
+    fn workers_do_not_wait_for_each_other() {
+        let first = BlockingWorker::default();
+        let second = BlockingWorker::default();
+        let (release_sender, release_receiver) = mpsc::channel::<()>();
+
+        // the first worker is busy until the second one executes its call
+        let blocked = first.spawn_blocking(move || {
+            release_receiver
+                .recv()
+                .map_err(|_| CommonError::from(SyncError::Disconnected))
+        });
+
+        block_on(second.spawn_blocking(move || {
+            release_sender
+                .send(())
+                .map_err(|_| CommonError::from(SyncError::Disconnected))
+        }))
+        .unwrap();
+
+        assert!(block_on(blocked).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "simulated")]
+    fn async_state_operations() {
+        use crate::sdk::test_support::{find_led, simulated_backend};
+        use crate::{DeviceLedStateInput, MysticLightSDK};
+
+        let sdk = block_on(MysticLightSDK::with_backend_async(simulated_backend())).unwrap();
+        let led = find_led(&sdk, "JRGB1");
+
+        block_on(led.merge_with_state_async(&DeviceLedStateInput {
+            bright: Some(1),
+            ..Default::default()
+        }))
+        .unwrap();
+
+        let state = block_on(led.get_state_async()).unwrap();
+
+        assert_eq!(state.bright, 1);
+        assert_eq!(state.style, "Breathing");
+    }
+}
@@ -0,0 +1,61 @@
+//! Helpers shared by the tests that run against the [SimulatedBackend]
+
+#[cfg(feature = "recording")]
+use std::io::Write;
+#[cfg(feature = "recording")]
+use std::sync::{Arc, Mutex};
+
+use crate::{Capability, DeviceLed, MysticLightSDK, SimulatedBackend};
+
+/// Fixture with the motherboard, gpu and keyboard, see `examples/fixtures/simulated.json`
+pub(crate) const FIXTURE: &str = include_str!("../../examples/fixtures/simulated.json");
+
+/// returns backend simulating the [FIXTURE] hardware
+pub(crate) fn simulated_backend() -> SimulatedBackend {
+    SimulatedBackend::from_json(FIXTURE).unwrap()
+}
+
+/// returns sdk initialized with the [simulated_backend]
+pub(crate) fn simulated_sdk() -> MysticLightSDK {
+    MysticLightSDK::with_backend(simulated_backend()).unwrap()
+}
+
+/// returns sdk initialized with the [simulated_backend] that lacks the passed optional sdk functions
+pub(crate) fn simulated_sdk_without(missing_capabilities: Vec<Capability>) -> MysticLightSDK {
+    let mut fixture = simulated_backend().fixture().clone();
+    fixture.missing_capabilities = missing_capabilities;
+
+    MysticLightSDK::with_backend(SimulatedBackend::new(fixture)).unwrap()
+}
+
+/// returns led by its name among leds of all the devices
+pub(crate) fn find_led<'a>(sdk: &'a MysticLightSDK, name: &str) -> &'a DeviceLed {
+    sdk.devices_iter()
+        .flat_map(|device| device.leds_iter())
+        .find(|led| led.name() == name)
+        .unwrap()
+}
+
+/// Writer that collects everything written through any of its clones e.g. the trace of the [RecordingBackend](crate::RecordingBackend)
+#[cfg(feature = "recording")]
+#[derive(Clone, Default)]
+pub(crate) struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+#[cfg(feature = "recording")]
+impl SharedBuffer {
+    /// returns everything written since the previous call
+    pub(crate) fn take(&self) -> String {
+        String::from_utf8(std::mem::take(&mut *self.0.lock().unwrap())).unwrap()
+    }
+}
+
+#[cfg(feature = "recording")]
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt::Display;
+
+use custom_error::custom_error;
+
+use super::error::CommonError;
+use super::led::{DeviceLed, DeviceLedState, DeviceLedStateInput};
+
+/// Leds restored by the rollback of the failed [LedTransaction]
+#[derive(Debug)]
+pub struct RollbackReport {
+    /// led which change has failed
+    pub failed: DeviceLed,
+    /// leds restored to their prior state in the order of restoration
+    pub restored: Vec<DeviceLed>,
+    /// leds that cannot be restored together with the restoration errors. These leds might be left half-updated
+    pub not_restored: Vec<(DeviceLed, CommonError)>,
+}
+
+impl RollbackReport {
+    /// returns whether every changed led is restored to its prior state
+    pub fn is_complete(&self) -> bool {
+        self.not_restored.is_empty()
+    }
+}
+
+impl Display for RollbackReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "change of {} failed, restored {} leds",
+            self.failed.name(),
+            self.restored.len()
+        )?;
+
+        for (led, error) in &self.not_restored {
+            write!(f, ", cannot restore {}: {error}", led.name())?;
+        }
+
+        Ok(())
+    }
+}
+
+custom_error! {
+  /// Errors of the [LedTransaction]
+  #[non_exhaustive]
+  pub TransactionError
+      /// Prior state of the led cannot be read, nothing is changed
+      Capture{led: String, source: CommonError} = "Cannot read state of {led}: {source}",
+      /// Change has failed and the already applied changes are rolled back
+      RolledBack{source: CommonError, report: Box<RollbackReport>} = "{source}: {report}",
+      /// Thread that applies the transaction has failed, so the state of the leds is unknown
+      Interrupted{source: CommonError} = "Transaction is interrupted: {source}",
+}
+
+/// Set of led state changes that are applied all together or not at all. Leds may belong to the different devices
+///
+/// Prior states of all the leds are captured before any change. If some change fails, every already changed led
+/// and the failed one are restored to their prior states in the reverse order. Parameters that are not honoured by the prior style
+/// are skipped, see [DeviceLed::style_capabilities]
+///
+/// # Examples
+///
+/// ```ignore
+/// use mystic_light_sdk::LedTransaction;
+///
+/// LedTransaction::default()
+///     .set_state(&motherboard_led, state.clone())
+///     .set_state(&gpu_led, state)
+///     .apply()?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LedTransaction {
+    changes: Vec<(DeviceLed, DeviceLedState)>,
+}
+
+impl LedTransaction {
+    /// Add the change of the led state. Changes are applied in the order they were added
+    pub fn set_state(&mut self, led: &DeviceLed, state: DeviceLedState) -> &mut Self {
+        self.changes.push((led.clone(), state));
+        self
+    }
+
+    /// returns whether the transaction has no changes
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Apply all the changes or restore prior states of the leds on the first failure
+    #[tracing::instrument(level = "debug", skip(self), fields(changes = self.changes.len()))]
+    pub fn apply(&self) -> Result<(), TransactionError> {
+        let prior_states = self
+            .changes
+            .iter()
+            .map(|(led, _)| {
+                led.get_state().map_err(|source| TransactionError::Capture {
+                    led: led.name().to_owned(),
+                    source,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (applied, (led, state)) in self.changes.iter().enumerate() {
+            let Err(source) = led.set_state(state) else {
+                continue;
+            };
+
+            tracing::warn!(led = led.name(), %source, "Led change failed, rolling back");
+
+            let mut report = RollbackReport {
+                failed: led.clone(),
+                restored: Vec::new(),
+                not_restored: Vec::new(),
+            };
+
+            for ((led, _), prior_state) in self.changes[..=applied].iter().zip(&prior_states).rev()
+            {
+                match restore(led, prior_state) {
+                    Ok(()) => report.restored.push(led.clone()),
+                    Err(error) => {
+                        tracing::warn!(led = led.name(), %error, "Cannot restore led state");
+
+                        report.not_restored.push((led.clone(), error));
+                    }
+                }
+            }
+
+            return Err(TransactionError::RolledBack {
+                source,
+                report: Box::new(report),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Async version of the [LedTransaction::apply]
+    ///
+    /// Sdk calls are executed on the separate thread, so the returned future doesn't block and may be awaited by any async runtime
+    pub async fn apply_async(&self) -> Result<(), TransactionError> {
+        let Some((led, _)) = self.changes.first() else {
+            return Ok(());
+        };
+
+        let transaction = self.clone();
+
+        // leds of the transaction may belong to different backends, the worker of the first one is used
+        led.spawn_blocking(move || Ok(transaction.apply()))
+            .await
+            .unwrap_or_else(|source| Err(TransactionError::Interrupted { source }))
+    }
+}
+
+/// Restore prior state of the led skipping parameters that are not honoured by its style e.g. the sdk rejects
+/// the color write for some of the styles. Every parameter is written if the style is not supported by the led
+fn restore(led: &DeviceLed, state: &DeviceLedState) -> Result<(), CommonError> {
+    let capabilities = led.style_capabilities(&state.style);
+
+    // known state of the led is not trusted after the failure, so every parameter is written
+    led.merge_with_state_forced(&DeviceLedStateInput {
+        style: Some(state.style.clone()),
+        color: capabilities
+            .as_ref()
+            .is_none_or(|capabilities| capabilities.color)
+            .then_some(state.color.clone()),
+        bright: capabilities
+            .as_ref()
+            .is_none_or(|capabilities| capabilities.bright)
+            .then_some(state.bright),
+        speed: capabilities
+            .as_ref()
+            .is_none_or(|capabilities| capabilities.speed)
+            .then_some(state.speed),
+    })
+}
+
+impl DeviceLed {
+    /// Transactional version of the [DeviceLed::set_state]. Prior state of the led is restored if any of the writes fails
+    pub fn set_state_transactional(&self, state: &DeviceLedState) -> Result<(), TransactionError> {
+        LedTransaction::default()
+            .set_state(self, state.clone())
+            .apply()
+    }
+}
+
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use super::*;
+    use crate::sdk::test_support::{find_led, simulated_sdk};
+    use crate::{Color, LedStyle, UsageError};
+
+    fn state(bright: u32) -> DeviceLedState {
+        DeviceLedState {
+            style: LedStyle::Breathing,
+            color: Color {
+                red: 1,
+                green: 2,
+                blue: 3,
+            },
+            bright,
+            speed: 1,
+        }
+    }
+
+    #[test]
+    fn applies_changes_across_devices() {
+        let sdk = simulated_sdk();
+        let jrgb = find_led(&sdk, "JRGB1");
+        let vga = find_led(&sdk, "VGA");
+
+        LedTransaction::default()
+            .set_state(jrgb, state(1))
+            .set_state(vga, state(2))
+            .apply()
+            .unwrap();
+
+        assert_eq!(jrgb.get_state().unwrap(), state(1));
+        assert_eq!(vga.get_state().unwrap(), state(2));
+    }
+
+    #[test]
+    fn failed_change_is_rolled_back() {
+        let sdk = simulated_sdk();
+        let jrgb = find_led(&sdk, "JRGB1");
+        let vga = find_led(&sdk, "VGA");
+
+        let jrgb_prior = jrgb.get_state().unwrap();
+        let vga_prior = vga.get_state().unwrap();
+
+        // style of the vga is changed before the brightness write fails, its prior style ignores the color
+        let result = LedTransaction::default()
+            .set_state(jrgb, state(1))
+            .set_state(vga, state(100))
+            .apply();
+
+        let Err(TransactionError::RolledBack { source, report }) = result else {
+            panic!("transaction should be rolled back");
+        };
+
+        assert!(matches!(
+            source,
+            CommonError::UsageError {
+                source: UsageError::ExcessBrightLevel { .. }
+            }
+        ));
+        assert_eq!(report.failed.name(), "VGA");
+        assert!(report.is_complete());
+
+        let restored: Vec<_> = report.restored.iter().map(|led| led.name()).collect();
+
+        assert_eq!(restored, vec!["VGA", "JRGB1"]);
+        assert_eq!(jrgb.get_state().unwrap(), jrgb_prior);
+        assert_eq!(vga.get_state().unwrap(), vga_prior);
+    }
+}
@@ -1,5 +1,6 @@
-use winapi::shared::minwindef::DWORD;
+#[cfg(windows)]
 use winapi::shared::wtypes::BSTR;
+#[cfg(windows)]
 use winapi::um::oaidl::SAFEARRAY;
 
 use crate::CommonError;
@@ -9,16 +10,31 @@ pub type MysticLightSdkResult = i32;
 pub type Result<T> = std::result::Result<T, CommonError>;
 
 // msi c++ types
+#[cfg(windows)]
 pub type DeviceName = BSTR;
+#[cfg(windows)]
+pub type DeviceNames = *mut SAFEARRAY;
+#[cfg(windows)]
 pub type DeviceTypes = *mut SAFEARRAY;
+#[cfg(windows)]
 pub type LedCounts = *mut SAFEARRAY;
-pub type LedIndex = DWORD;
+#[cfg(windows)]
+pub type ErrorDescription = BSTR;
+#[cfg(windows)]
 pub type LedName = BSTR;
-pub type LedStyle = BSTR;
+#[cfg(windows)]
+pub type LedNames = *mut SAFEARRAY;
+#[cfg(windows)]
+pub type LedStyleName = BSTR;
+#[cfg(windows)]
 pub type LedStyles = *mut SAFEARRAY;
-pub type ColorLevel = DWORD;
-pub type BrightLevel = DWORD;
-pub type SpeedLevel = DWORD;
+
+// msi c++ DWORD types
+pub type DeviceIndex = u32;
+pub type LedIndex = u32;
+pub type ColorLevel = u32;
+pub type BrightLevel = u32;
+pub type SpeedLevel = u32;
 
 /// used to filter specific objects
 pub trait Filter<T> {
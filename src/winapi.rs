@@ -6,11 +6,18 @@
 //! - [widestring](https://docs.rs/widestring/0.4.3/widestring/index.html) to get windows compatible UTF wide strings
 //! - [oaidl](https://docs.rs/oaidl/latest/oaidl/index.html) to make conversion between widestring and WinAPI types
 //!
+use std::fmt::Display;
+use std::mem::ManuallyDrop;
 use std::vec::IntoIter;
 
 use oaidl::{BStringExt, Ptr, SafeArrayExt};
 use widestring::U16String;
-use winapi::{shared::wtypes::BSTR, um::oaidl::SAFEARRAY};
+use winapi::{
+    shared::wtypes::BSTR,
+    um::{oaidl::SAFEARRAY, oleauto::SafeArrayDestroy},
+};
+
+use crate::ConversionError;
 
 /// Wrapper for the BSTR.
 ///
@@ -31,33 +38,25 @@ impl Bstr {
     }
 }
 
-impl Default for Bstr {
+impl Bstr {
     /// Create new wrapper for the empty BSTR string
-    ///
-    /// # Panics
-    ///
-    /// - In case of error while allocating BSTR string
-    fn default() -> Self {
-        Bstr {
-            bstr: U16String::new()
-                .allocate_bstr()
-                .expect("Cannot create BSTR"),
-        }
+    pub fn empty() -> Result<Self, ConversionError> {
+        Ok(Bstr {
+            bstr: U16String::new().allocate_bstr().map_err(bstr_error)?,
+        })
     }
 }
 
-impl From<&str> for Bstr {
+impl TryFrom<&str> for Bstr {
+    type Error = ConversionError;
+
     /// Creates BSTR wrapper from the string
-    ///
-    /// # Panics
-    ///
-    /// - In case of any errors while converting to the BSTR
-    fn from(s: &str) -> Self {
-        Bstr {
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Ok(Bstr {
             bstr: U16String::from_str(s)
                 .consume_to_bstr()
-                .expect("Cannot convert string to BSTR"),
-        }
+                .map_err(bstr_error)?,
+        })
     }
 }
 
@@ -70,13 +69,20 @@ impl From<&Bstr> for BSTR {
     }
 }
 
-impl From<BSTR> for Bstr {
-    fn from(bstr: BSTR) -> Self {
-        Self {
+impl TryFrom<BSTR> for Bstr {
+    type Error = ConversionError;
+
+    /// Copies BSTR string returned by the FFI into the wrapper
+    fn try_from(bstr: BSTR) -> Result<Self, Self::Error> {
+        if bstr.is_null() {
+            return Err(ConversionError::NullPointer);
+        }
+
+        Ok(Self {
             bstr: U16String::from_bstr(bstr)
                 .consume_to_bstr()
-                .expect("Cannot convert string to BSTR"),
-        }
+                .map_err(bstr_error)?,
+        })
     }
 }
 
@@ -86,48 +92,109 @@ impl Drop for Bstr {
     }
 }
 
-impl ToString for Bstr {
-    fn to_string(&self) -> String {
-        U16String::from_bstr(self.bstr.as_ptr()).to_string_lossy()
+impl Display for Bstr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&U16String::from_bstr(self.bstr.as_ptr()).to_string_lossy())
+    }
+}
+
+fn bstr_error(error: impl Display) -> ConversionError {
+    ConversionError::Bstr {
+        message: error.to_string(),
+    }
+}
+
+fn safearray_error(error: impl Display) -> ConversionError {
+    ConversionError::SafeArray {
+        message: error.to_string(),
     }
 }
 
 /// Trait that implements basic conversions from SAFEARRAY to Rust types
-pub trait FromSafeArray {
-    fn from_safearray(safearray: *mut SAFEARRAY) -> Self;
+pub trait TryFromSafeArray: Sized {
+    fn try_from_safearray(safearray: *mut SAFEARRAY) -> Result<Self, ConversionError>;
 }
 
-impl<T: FromIterator<String>> FromSafeArray for T {
+impl<T: FromIterator<String>> TryFromSafeArray for T {
     /// Converts SAFEARRAY to FromIterator<String>
     ///
+    /// Returns [ConversionError] on null pointer or any error happened during conversion from SAFEARRAY
+    ///
     /// # Examples
     ///
     /// ```ignore
     /// use std::collections::HashSet;
     /// use std::ptr::null_mut;
     /// use winapi::um::oaidl::SAFEARRAY;
-    /// use mystic_light_sdk::winapi::FromSafeArray;
+    /// use mystic_light_sdk::winapi::TryFromSafeArray;
     ///
     /// let array: *mut SAFEARRAY = null_mut();
     ///
     /// // fill the array somehow
     ///
-    /// let vec: Vec<String> = Vec::from_safearray(array);
+    /// let vec: Vec<String> = Vec::try_from_safearray(array)?;
     ///
-    /// let hashSet: HashSet<String> = HashSet::from_safearray(array);
+    /// let hashSet: HashSet<String> = HashSet::try_from_safearray(array)?;
     /// ```
+    fn try_from_safearray(array: *mut SAFEARRAY) -> Result<Self, ConversionError> {
+        let ptr = Ptr::with_checked(array).ok_or(ConversionError::NullPointer)?;
+
+        let vec = IntoIter::<U16String>::from_safearray(ptr).map_err(safearray_error)?;
+
+        Ok(vec.iter().map(|s| s.to_string_lossy()).collect())
+    }
+}
+
+/// Wrapper for the SAFEARRAY of BSTR strings.
+///
+/// # Features
+///
+/// - allocates SAFEARRAY from the Rust strings
+/// - frees memory after usage thanks to the Drop trait
+pub struct SafeArray {
+    array: Ptr<SAFEARRAY>,
+}
+
+impl SafeArray {
+    /// Returns inner pointer to the SAFEARRAY.
     ///
-    /// # Panics
-    ///
-    /// - passing null pointer as safearray
-    /// - in case of any error happened during conversion from SAFEARRAY
-    fn from_safearray(array: *mut SAFEARRAY) -> Self {
-        let ptr = Ptr::with_checked(array).expect("Got null pointer");
+    /// This method should be used to get actually SAFEARRAY that most of the FFI expects
+    pub fn as_ptr(&self) -> *mut SAFEARRAY {
+        self.array.as_ptr()
+    }
+
+    /// Returns inner pointer to the SAFEARRAY and passes ownership over the memory to the caller
+    pub fn into_raw(self) -> *mut SAFEARRAY {
+        ManuallyDrop::new(self).as_ptr()
+    }
+}
 
-        let vec =
-            IntoIter::<U16String>::from_safearray(ptr).expect("Cannot convert from SAFEARRAY");
+impl SafeArray {
+    /// Creates SAFEARRAY of BSTR strings
+    pub fn try_from_iter<S, I>(iter: I) -> Result<Self, ConversionError>
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = S>,
+    {
+        let strings: Vec<U16String> = iter
+            .into_iter()
+            .map(|s| U16String::from_str(s.as_ref()))
+            .collect();
 
-        vec.iter().map(|s| s.to_string_lossy()).collect()
+        Ok(SafeArray {
+            array: strings
+                .into_iter()
+                .into_safearray()
+                .map_err(safearray_error)?,
+        })
+    }
+}
+
+impl Drop for SafeArray {
+    fn drop(&mut self) {
+        unsafe {
+            SafeArrayDestroy(self.array.as_ptr());
+        }
     }
 }
 
@@ -141,7 +208,7 @@ mod tests {
     fn convert_to_bstr_and_backward() {
         let s = "some test string";
 
-        let bstr = Bstr::from(s);
+        let bstr = Bstr::try_from(s).unwrap();
 
         assert_eq!(bstr.to_string(), s);
     }
@@ -150,9 +217,9 @@ mod tests {
     fn convert_from_raw_bstr() {
         let s = "some test string";
 
-        let bstr = Bstr::from(s);
+        let bstr = Bstr::try_from(s).unwrap();
 
-        let bstr_from = Bstr::from(bstr.as_ptr());
+        let bstr_from = Bstr::try_from(bstr.as_ptr()).unwrap();
 
         assert_eq!(bstr_from.to_string(), s);
     }
@@ -167,7 +234,7 @@ mod tests {
 
         let safe_array = v.into_iter().into_safearray().unwrap();
 
-        let parsed = Vec::from_safearray(safe_array.as_ptr());
+        let parsed = Vec::try_from_safearray(safe_array.as_ptr()).unwrap();
 
         assert_eq!(parsed, vec!["test1", "test2", "test3"]);
     }
@@ -182,11 +249,32 @@ mod tests {
 
         let safe_array = v.into_iter().into_safearray().unwrap();
 
-        let parsed: HashSet<String> = HashSet::from_safearray(safe_array.as_ptr());
+        let parsed: HashSet<String> = HashSet::try_from_safearray(safe_array.as_ptr()).unwrap();
 
         assert_eq!(parsed.len(), 3);
         assert!(parsed.contains("test1"));
         assert!(parsed.contains("test2"));
         assert!(parsed.contains("test3"));
     }
+
+    #[test]
+    fn convert_strings_to_safearray_and_backward() {
+        let safe_array = SafeArray::try_from_iter(["test1", "test2", "test3"]).unwrap();
+
+        let parsed = Vec::try_from_safearray(safe_array.into_raw()).unwrap();
+
+        assert_eq!(parsed, vec!["test1", "test2", "test3"]);
+    }
+
+    #[test]
+    fn null_pointers_are_conversion_errors() {
+        assert!(matches!(
+            Vec::<String>::try_from_safearray(std::ptr::null_mut()),
+            Err(ConversionError::NullPointer)
+        ));
+        assert!(matches!(
+            Bstr::try_from(std::ptr::null_mut() as BSTR),
+            Err(ConversionError::NullPointer)
+        ));
+    }
 }